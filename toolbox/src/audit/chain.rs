@@ -0,0 +1,199 @@
+use std::{
+    fmt,
+    fs::File,
+    io::{self, BufRead, BufReader},
+    path::Path,
+};
+
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+
+use crate::audit::event::AuditEvent;
+
+/// 链首条目的 prev
+pub const GENESIS: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// 哈希字段, 始终位于行尾
+const HASH_KEY: &str = ",\"hash\":\"";
+
+/// 已写入哈希链的审计条目
+#[derive(Debug, Clone)]
+pub struct AuditEntry {
+    pub seq: u64,
+    pub time: OffsetDateTime,
+    pub event: AuditEvent,
+    pub prev: String,
+    pub hash: String,
+    line: String,
+}
+
+impl AuditEntry {
+    /// 计算哈希: sha256(不含 hash 字段的 JSON)
+    pub(crate) fn new(seq: u64, time: OffsetDateTime, event: AuditEvent, prev: String) -> Self {
+        let body = json!({
+            "seq": seq,
+            "time": time.format(&Rfc3339).ok(),
+            "event": event,
+            "prev": prev,
+        })
+        .to_string();
+        let hash = digest(&body);
+        let line = format!("{}{HASH_KEY}{hash}\"}}", &body[..body.len() - 1]);
+        Self {
+            seq,
+            time,
+            event,
+            prev,
+            hash,
+            line,
+        }
+    }
+
+    /// 单行 JSON, 含 hash 字段
+    pub fn line(&self) -> &str {
+        &self.line
+    }
+}
+
+fn digest(body: &str) -> String {
+    Sha256::digest(body.as_bytes())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// 拆分出不含 hash 的 JSON 与 hash
+fn split(line: &str) -> Option<(String, &str)> {
+    let i = line.rfind(HASH_KEY)?;
+    let hash = line[i + HASH_KEY.len()..].strip_suffix("\"}")?;
+    Some((format!("{}}}", &line[..i]), hash))
+}
+
+/// 校验结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verified {
+    pub entries: u64,
+    /// 最后一条的序号与哈希, 用于继续校验下一个文件
+    pub last_seq: Option<u64>,
+    pub last_hash: Option<String>,
+}
+
+/// 校验失败, line 从 1 开始
+#[derive(Debug)]
+pub enum VerifyError {
+    Io(io::Error),
+    /// 无法解析
+    Malformed {
+        line: usize,
+    },
+    /// 内容与哈希不符
+    Hash {
+        line: usize,
+    },
+    /// prev 与上一条哈希不符
+    Chain {
+        line: usize,
+    },
+    /// 序号不连续
+    Sequence {
+        line: usize,
+        expected: u64,
+        found: u64,
+    },
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "读取审计日志失败: {err}"),
+            Self::Malformed { line } => write!(f, "第 {line} 行: 格式错误"),
+            Self::Hash { line } => write!(f, "第 {line} 行: 内容与哈希不符"),
+            Self::Chain { line } => write!(f, "第 {line} 行: 与上一条哈希不连续"),
+            Self::Sequence { line, expected, found } => {
+                write!(f, "第 {line} 行: 序号应为 {expected}, 实际为 {found}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+impl From<io::Error> for VerifyError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// 校验哈希链
+///
+/// prev 为上一个文件最后一条的哈希, None 时信任首条的 prev 与序号
+pub fn verify(reader: impl BufRead, prev: Option<&str>) -> Result<Verified, VerifyError> {
+    let mut last_hash = prev.map(String::from);
+    let mut last_seq = None;
+    let mut entries = 0;
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let n = i + 1;
+        if line.is_empty() {
+            continue;
+        }
+        let (body, hash) = split(&line).ok_or(VerifyError::Malformed { line: n })?;
+        let value: Value = serde_json::from_str(&body).map_err(|_| VerifyError::Malformed { line: n })?;
+        let (Some(seq), Some(entry_prev)) = (value["seq"].as_u64(), value["prev"].as_str()) else {
+            return Err(VerifyError::Malformed { line: n });
+        };
+        if digest(&body) != hash {
+            return Err(VerifyError::Hash { line: n });
+        }
+        if last_hash.as_deref().is_some_and(|last| last != entry_prev) {
+            return Err(VerifyError::Chain { line: n });
+        }
+        if let Some(last) = last_seq
+            && seq != last + 1
+        {
+            return Err(VerifyError::Sequence {
+                line: n,
+                expected: last + 1,
+                found: seq,
+            });
+        }
+        last_hash = Some(hash.to_string());
+        last_seq = Some(seq);
+        entries += 1;
+    }
+    Ok(Verified {
+        entries,
+        last_seq,
+        last_hash,
+    })
+}
+
+/// 校验审计日志文件
+pub fn verify_file(path: impl AsRef<Path>, prev: Option<&str>) -> Result<Verified, VerifyError> {
+    verify(BufReader::new(File::open(path)?), prev)
+}
+
+/// 读取文件最后一条的序号与哈希, 文件不存在或为空时返回 None
+pub(crate) fn tail(path: &Path) -> io::Result<Option<(u64, String)>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    let mut last = None;
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if !line.is_empty() {
+            last = Some(line);
+        }
+    }
+    let Some(line) = last else {
+        return Ok(None);
+    };
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "审计日志最后一行格式错误");
+    let (body, hash) = split(&line).ok_or_else(invalid)?;
+    let value: Value = serde_json::from_str(&body).map_err(|_| invalid())?;
+    let seq = value["seq"].as_u64().ok_or_else(invalid)?;
+    Ok(Some((seq, hash.to_string())))
+}
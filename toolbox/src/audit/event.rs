@@ -0,0 +1,138 @@
+use std::borrow::Cow;
+
+use salvo::{Depot, Request};
+use serde::{Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use crate::{audit::logger::record, logger::request_id::RequestId, real_ip::RealIp};
+
+/// 审计事件类型
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditKind {
+    Login,
+    Logout,
+    /// 签发 token
+    TokenIssued,
+    /// 身份认证失败
+    AuthFailure,
+    /// 权限不足
+    AccessDenied,
+    /// 管理操作
+    AdminAction,
+    Custom(Cow<'static, str>),
+}
+
+impl AuditKind {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Login => "login",
+            Self::Logout => "logout",
+            Self::TokenIssued => "token_issued",
+            Self::AuthFailure => "auth_failure",
+            Self::AccessDenied => "access_denied",
+            Self::AdminAction => "admin_action",
+            Self::Custom(kind) => kind,
+        }
+    }
+}
+
+impl Serialize for AuditKind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// 操作结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Success,
+    Failure,
+}
+
+/// 审计事件
+///
+/// ```ignore
+/// AuditEvent::new(AuditKind::Login)
+///     .actor(&user.name)
+///     .request(req, depot)
+///     .record();
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEvent {
+    pub kind: AuditKind,
+    pub outcome: Outcome,
+    /// 操作者
+    pub actor: Option<String>,
+    /// 操作对象
+    pub resource: Option<String>,
+    pub ip: Option<String>,
+    pub request_id: Option<String>,
+    pub details: Map<String, Value>,
+}
+
+impl AuditEvent {
+    pub fn new(kind: AuditKind) -> Self {
+        Self {
+            kind,
+            outcome: Outcome::Success,
+            actor: None,
+            resource: None,
+            ip: None,
+            request_id: None,
+            details: Map::new(),
+        }
+    }
+
+    /// 自定义类型
+    pub fn custom(kind: impl Into<Cow<'static, str>>) -> Self {
+        Self::new(AuditKind::Custom(kind.into()))
+    }
+
+    pub fn outcome(mut self, outcome: Outcome) -> Self {
+        self.outcome = outcome;
+        self
+    }
+
+    pub fn failure(self) -> Self {
+        self.outcome(Outcome::Failure)
+    }
+
+    pub fn actor(mut self, actor: impl Into<String>) -> Self {
+        self.actor = Some(actor.into());
+        self
+    }
+
+    pub fn resource(mut self, resource: impl Into<String>) -> Self {
+        self.resource = Some(resource.into());
+        self
+    }
+
+    pub fn detail(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.details.insert(key.into(), value.into());
+        self
+    }
+
+    /// 从请求中读取客户端 IP 与请求 ID
+    pub fn request(self, req: &Request, depot: &Depot) -> Self {
+        let mut event = self.connection(req);
+        event.request_id = RequestId::current(req, depot).map(|id| id.0.to_string());
+        event
+    }
+
+    /// 同 [`request`](Self::request), 无 Depot 时仅读取请求扩展
+    pub(crate) fn connection(mut self, req: &Request) -> Self {
+        let ip = match req.extensions().get::<RealIp>() {
+            Some(ip) => Some(ip.0),
+            None => req.remote_addr().clone().into_std().map(|addr| addr.ip()),
+        };
+        self.ip = ip.map(|ip| ip.to_string());
+        self.request_id = req.extensions().get::<RequestId>().map(|id| id.0.to_string());
+        self
+    }
+
+    /// 写入全局审计日志, 未安装时忽略
+    pub fn record(self) {
+        record(self)
+    }
+}
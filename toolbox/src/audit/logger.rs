@@ -0,0 +1,154 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    thread,
+};
+
+use crossbeam_channel::{Receiver, Sender};
+
+use crate::{
+    audit::{
+        chain::{AuditEntry, GENESIS, tail},
+        event::AuditEvent,
+    },
+    logger::{
+        clock::clock,
+        output::{OutFile, Output, OutputMethod},
+    },
+};
+
+static AUDIT: OnceLock<AuditLogger> = OnceLock::new();
+
+/// 审计日志, 在独立线程中为事件编号并计算哈希链后写入各输出
+///
+/// 输出应为审计专用, 与访问日志混写会导致校验失败
+#[derive(Clone)]
+pub struct AuditLogger {
+    sender: Sender<AuditEvent>,
+}
+
+impl AuditLogger {
+    pub fn new(writers: Vec<OutputMethod>) -> Self {
+        Self::spawn(writers, 0, GENESIS.into())
+    }
+
+    /// 从已有文件的最后一条继续哈希链
+    pub fn resume(writers: Vec<OutputMethod>, path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(match tail(path.as_ref())? {
+            Some((seq, hash)) => Self::spawn(writers, seq + 1, hash),
+            None => Self::new(writers),
+        })
+    }
+
+    /// 按天写入 `{dir}/audit-{date}.log`, 从目录中最新的审计日志继续哈希链
+    pub fn file(dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+        let latest = latest(&dir)?;
+        let file = OutFile::new(dir, "audit-{date}.log".into(), None)?;
+        Ok(match latest {
+            Some((seq, hash)) => Self::spawn(vec![file.into()], seq + 1, hash),
+            None => Self::new(vec![file.into()]),
+        })
+    }
+
+    fn spawn(writers: Vec<OutputMethod>, seq: u64, prev: String) -> Self {
+        let (sender, rx) = crossbeam_channel::unbounded::<AuditEvent>();
+        thread::spawn(move || run(rx, writers, seq, prev));
+        Self { sender }
+    }
+
+    pub fn record(&self, event: AuditEvent) {
+        if let Err(err) = self.sender.send(event) {
+            eprintln!("Send 审计日志时出现错误 {err}")
+        }
+    }
+}
+
+/// 目录中最新的非空审计日志的最后一条, 文件名中的日期按字典序即时间顺序
+fn latest(dir: &Path) -> io::Result<Option<(u64, String)>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            name.starts_with("audit-") && name.ends_with(".log")
+        })
+        .collect();
+    files.sort_unstable_by(|a, b| b.cmp(a));
+    for path in files {
+        if let Some(last) = tail(&path)? {
+            return Ok(Some(last));
+        }
+    }
+    Ok(None)
+}
+
+fn run(rx: Receiver<AuditEvent>, mut writers: Vec<OutputMethod>, mut seq: u64, mut prev: String) {
+    for event in rx {
+        let entry = AuditEntry::new(seq, clock().now(), event, prev);
+        for writer in &mut writers {
+            if let Err(err) = writer.audit(&entry) {
+                eprintln!("审计日志输出失败: {err}")
+            }
+        }
+        seq += 1;
+        prev = entry.hash;
+    }
+}
+
+/// 设置全局审计日志, 只能设置一次, 设置后 auth 模块的事件会自动记录
+pub fn install(logger: AuditLogger) -> Result<(), AuditLogger> {
+    AUDIT.set(logger)
+}
+
+/// 写入全局审计日志, 未设置时忽略
+pub fn record(event: AuditEvent) {
+    if let Some(logger) = AUDIT.get() {
+        logger.record(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use time::macros::datetime;
+
+    use super::*;
+    use crate::audit::{AuditKind, chain::verify_file};
+
+    #[test]
+    fn resume_from_latest_file() {
+        let dir = std::env::temp_dir().join(format!("toolbox-audit-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut prev = GENESIS.to_string();
+        for (day, seqs) in [("2024-01-01", 0..2), ("2024-01-02", 2..5)] {
+            let mut file = fs::File::create(dir.join(format!("audit-{day}.log"))).unwrap();
+            for seq in seqs {
+                let entry = AuditEntry::new(
+                    seq,
+                    datetime!(2024-01-01 0:00 UTC),
+                    AuditEvent::new(AuditKind::Login),
+                    prev,
+                );
+                writeln!(file, "{}", entry.line()).unwrap();
+                prev = entry.hash;
+            }
+        }
+        // 当天已创建但为空的文件不影响续接
+        fs::File::create(dir.join("audit-2024-01-03.log")).unwrap();
+
+        assert_eq!(latest(&dir).unwrap(), Some((4, prev.clone())));
+        let first = verify_file(dir.join("audit-2024-01-01.log"), None).unwrap();
+        assert!(verify_file(dir.join("audit-2024-01-02.log"), first.last_hash.as_deref()).is_ok());
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,7 @@
+pub mod chain;
+pub mod event;
+pub mod logger;
+
+pub use chain::{AuditEntry, Verified, VerifyError, verify, verify_file};
+pub use event::{AuditEvent, AuditKind, Outcome};
+pub use logger::{AuditLogger, install, record};
@@ -1,3 +1,5 @@
+use std::any::type_name;
+
 use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation};
 use salvo::{
     Request,
@@ -6,7 +8,11 @@ use salvo::{
 use serde::{Deserialize, Serialize};
 use time::UtcDateTime;
 
-use crate::{res, resp::Res};
+use crate::{
+    audit::{AuditEvent, AuditKind},
+    res,
+    resp::Res,
+};
 
 /// 秘钥
 #[derive(Clone)]
@@ -46,18 +52,34 @@ where
         if let Some(value) = req.extensions_mut().remove::<Self>() {
             return Ok(value);
         }
-        let token = req
-            .headers()
-            .typed_get::<Authorization<Bearer>>()
-            .ok_or(res!(401, "身份认证失败: 请求未携带有效token"))?;
-        Ok(Self::decode(token.token())?)
+        let result = match req.headers().typed_get::<Authorization<Bearer>>() {
+            Some(token) => Self::decode(token.token()).map_err(Res::from),
+            None => Err(res!(401, "身份认证失败: 请求未携带有效token").localize("auth.missing_token")),
+        };
+        if let Err(err) = &result {
+            AuditEvent::new(AuditKind::AuthFailure)
+                .failure()
+                .connection(req)
+                .resource(req.uri().path())
+                .detail("reason", err.info())
+                .record();
+        }
+        result
     }
 
     fn encode(self) -> jsonwebtoken::errors::Result<String> {
         let config = Self::config();
         let exp = UtcDateTime::now().unix_timestamp() + config.duration;
         let claims = Claims { data: self, exp };
-        jsonwebtoken::encode(&config.header, &claims, &config.encoding_key)
+        let result = jsonwebtoken::encode(&config.header, &claims, &config.encoding_key);
+        let event = AuditEvent::new(AuditKind::TokenIssued)
+            .resource(type_name::<Self>())
+            .detail("exp", exp);
+        match &result {
+            Ok(_) => event.record(),
+            Err(err) => event.failure().detail("reason", err.to_string()).record(),
+        }
+        result
     }
 
     fn decode(token: &str) -> jsonwebtoken::errors::Result<Self> {
@@ -1,16 +1,30 @@
-use std::marker::PhantomData;
+use std::{fmt, marker::PhantomData, sync::Arc};
 
 use salvo::{Depot, FlowCtrl, Handler, Request, Response, Writer, async_trait};
 
 use crate::{
     auth::jwt_config::JwtToken,
     compare::{always_false, str::CompareStr},
+    res,
+    resp::Res,
 };
 
-#[derive(Debug)]
+/// 授权规则, 返回 false 时拒绝访问
+type Authorize<T> = Arc<dyn Fn(&T, &Request) -> bool + Send + Sync>;
+
 pub struct JwtAuth<T, A> {
     _marker: PhantomData<T>,
     allow: A,
+    authorize: Option<Authorize<T>>,
+}
+
+impl<T, A: fmt::Debug> fmt::Debug for JwtAuth<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JwtAuth")
+            .field("allow", &self.allow)
+            .field("authorize", &self.authorize.is_some())
+            .finish()
+    }
 }
 
 impl<T: JwtToken, A: CompareStr> JwtAuth<T, A> {
@@ -19,8 +33,15 @@ impl<T: JwtToken, A: CompareStr> JwtAuth<T, A> {
         Self {
             allow,
             _marker: PhantomData::<T>,
+            authorize: None,
         }
     }
+
+    /// 认证通过后检查权限, 返回 false 时响应 403 并记录 AccessDenied 审计事件
+    pub fn authorize(mut self, authorize: impl Fn(&T, &Request) -> bool + Send + Sync + 'static) -> Self {
+        self.authorize = Some(Arc::new(authorize));
+        self
+    }
 }
 
 impl<T: JwtToken> Default for JwtAuth<T, fn(&str) -> bool> {
@@ -41,6 +62,11 @@ where
         }
 
         match T::parse(req) {
+            Ok(value) if self.authorize.as_ref().is_some_and(|authorize| !authorize(&value, req)) => {
+                let err: Res = res!(403, "权限不足").localize("auth.forbidden");
+                err.write(req, depot, res).await;
+                ctrl.skip_rest();
+            }
             Ok(value) => {
                 req.extensions_mut().insert(value);
             }
@@ -4,6 +4,14 @@ pub trait CompareStr: Clone {
     fn compare(&self, uri: &str) -> bool;
 }
 
+/// 类型擦除后的 CompareStr, 便于存放在非泛型结构中
+pub type SharedCompare = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// 将 CompareStr 转为 SharedCompare
+pub fn shared(compare: impl CompareStr + Send + Sync + 'static) -> SharedCompare {
+    Arc::new(move |uri| compare.compare(uri))
+}
+
 impl<T: Fn(&str) -> bool + Clone> CompareStr for T {
     fn compare(&self, uri: &str) -> bool {
         self(uri)
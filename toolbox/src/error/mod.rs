@@ -0,0 +1,238 @@
+use std::{borrow::Cow, error::Error, fmt};
+
+use salvo::{Depot, Request, Response, Writer, async_trait};
+
+use crate::resp::Res;
+
+pub type AppResult<T> = Result<Res<T>, AppError>;
+
+type BoxError = Box<dyn Error + Send + Sync>;
+
+/// 内部错误的通用提示
+const INTERNAL: &str = "服务器内部错误";
+
+/// 错误类别, 决定 HTTP 状态码
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    BadRequest,
+    Unauthorized,
+    Forbidden,
+    NotFound,
+    Conflict,
+    Validation,
+    TooManyRequests,
+    Internal,
+    Unavailable,
+}
+
+impl ErrorKind {
+    pub fn status(&self) -> u16 {
+        match self {
+            Self::BadRequest => 400,
+            Self::Unauthorized => 401,
+            Self::Forbidden => 403,
+            Self::NotFound => 404,
+            Self::Conflict => 409,
+            Self::Validation => 422,
+            Self::TooManyRequests => 429,
+            Self::Internal => 500,
+            Self::Unavailable => 503,
+        }
+    }
+
+    /// 其他 4xx 归为 BadRequest, 其他状态码归为 Internal
+    pub fn from_status(status: u16) -> Self {
+        match status {
+            401 => Self::Unauthorized,
+            403 => Self::Forbidden,
+            404 => Self::NotFound,
+            409 => Self::Conflict,
+            422 => Self::Validation,
+            429 => Self::TooManyRequests,
+            503 => Self::Unavailable,
+            400..500 => Self::BadRequest,
+            _ => Self::Internal,
+        }
+    }
+}
+
+/// 应用错误
+///
+/// message 返回给客户端, source 写入日志, 按 [`Exposure`](crate::resp::Exposure) 决定是否返回给客户端,
+/// 在返回 [`AppResult`] 的处理函数中, 任何实现了 [`Error`] 的类型都可以用 `?` 转换为 Internal;
+/// 返回 [`Resp`](crate::resp::Resp) 的处理函数需先转换为 AppError, 再由 `?` 转换为 Res
+///
+/// ```ignore
+/// async fn show(id: u64) -> AppResult<User> {
+///     let user = db.find(id).await?.ok_or(AppError::new(ErrorKind::NotFound, "用户不存在"))?;
+///     resolve!(user => 200, "OK")
+/// }
+///
+/// async fn list() -> Resp<Vec<User>> {
+///     let users = db.list().await.map_err(AppError::internal)?;
+///     resolve!(users => 200, "OK")
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AppError {
+    status: u16,
+    code: Option<i64>,
+    message: Cow<'static, str>,
+    source: Option<BoxError>,
+    /// 使用通用提示的内部错误, 写入响应时按请求语言翻译
+    internal: bool,
+}
+
+impl AppError {
+    pub fn new(kind: ErrorKind, message: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            status: kind.status(),
+            code: None,
+            message: message.into(),
+            source: None,
+            internal: false,
+        }
+    }
+
+    /// 内部错误, 客户端只能看到通用提示
+    pub fn internal(source: impl Into<BoxError>) -> Self {
+        let mut err = Self::new(ErrorKind::Internal, INTERNAL).source(source);
+        err.internal = true;
+        err
+    }
+
+    /// 设置 HTTP 状态码
+    pub fn status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// 设置业务码
+    pub fn code(mut self, code: i64) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    /// 设置底层错误
+    pub fn source(mut self, source: impl Into<BoxError>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        ErrorKind::from_status(self.status)
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// 是否为 [`internal`](Self::internal) 创建的内部错误
+    pub fn is_internal(&self) -> bool {
+        self.internal
+    }
+
+    /// 底层错误链, 由外到内
+    pub fn chain(&self) -> impl Iterator<Item = &(dyn Error + 'static)> {
+        let first = self.source.as_deref().map(|e| e as &(dyn Error + 'static));
+        std::iter::successors(first, |&e| e.source())
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// AppError 本身不实现 Error, 以便为所有错误类型提供转换
+impl<E: Error + Send + Sync + 'static> From<E> for AppError {
+    fn from(err: E) -> Self {
+        Self::internal(err)
+    }
+}
+
+impl From<Res> for AppError {
+    fn from(res: Res) -> Self {
+        let code = res.biz_code();
+        let status = res.status_code();
+        let mut err = Self::new(ErrorKind::from_status(status), res.info().to_string()).status(status);
+        if code != i64::from(status) {
+            err = err.code(code);
+        }
+        err
+    }
+}
+
+impl From<AppError> for Res {
+    fn from(err: AppError) -> Self {
+        // 错误链作为内部详情, 写入响应时记录日志
+        let chain = err.chain().map(|e| e.to_string()).collect::<Vec<_>>().join(" <- ");
+        let mut res = Res::new(err.status, err.message.into(), ());
+        if err.internal {
+            res = res.localize("error.internal");
+        }
+        if !chain.is_empty() {
+            res = res.detail(chain);
+        }
+        match err.code {
+            Some(code) => res.code(code),
+            None => res,
+        }
+    }
+}
+
+/// app_error! 变体字段, 实现 Error 时作为 source, 否则以 Debug 格式作为 source
+#[doc(hidden)]
+pub struct Source<T>(pub &'static str, pub T);
+
+#[doc(hidden)]
+pub trait ErrorSource {
+    fn into_source(self) -> BoxError;
+}
+
+impl<E: Error + Send + Sync + 'static> ErrorSource for Source<E> {
+    fn into_source(self) -> BoxError {
+        Box::new(self.1)
+    }
+}
+
+#[doc(hidden)]
+pub trait DebugSource {
+    fn into_source(self) -> BoxError;
+}
+
+impl<T: fmt::Debug> DebugSource for &Source<T> {
+    fn into_source(self) -> BoxError {
+        format!("{}({:?})", self.0, self.1).into()
+    }
+}
+
+#[async_trait]
+impl Writer for AppError {
+    async fn write(self, req: &mut Request, depot: &mut Depot, res: &mut Response) {
+        Res::from(self).write(req, depot, res).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn internal() {
+        let err = AppError::from(std::io::Error::other("disk full"));
+        assert!(err.is_internal());
+        assert_eq!(err.kind(), ErrorKind::Internal);
+        assert_eq!(err.chain().map(|e| e.to_string()).collect::<Vec<_>>(), ["disk full"]);
+
+        // 与通用提示相同的自定义消息不视为内部错误
+        let err = AppError::new(ErrorKind::Internal, INTERNAL);
+        assert!(!err.is_internal());
+
+        let res = Res::from(AppError::new(ErrorKind::Conflict, "已存在").code(40901));
+        assert_eq!(res.status_code(), 409);
+        assert_eq!(res.biz_code(), 40901);
+        assert_eq!(res.info(), "已存在");
+    }
+}
@@ -0,0 +1,207 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock},
+};
+
+static CATALOG: OnceLock<Catalog> = OnceLock::new();
+
+/// 内置消息, 键名后依次为中文, 英文, 日文, 参数使用 `{name}` 占位
+const BUILTIN: &[(&str, [&str; 3])] = &[
+    (
+        "auth.missing_token",
+        [
+            "身份认证失败: 请求未携带有效token",
+            "Authentication failed: no valid token provided",
+            "認証に失敗しました: 有効なトークンがありません",
+        ],
+    ),
+    (
+        "auth.invalid_token",
+        [
+            "身份认证失败: {error}",
+            "Authentication failed: {error}",
+            "認証に失敗しました: {error}",
+        ],
+    ),
+    (
+        "auth.forbidden",
+        ["权限不足", "Access denied", "アクセスが拒否されました"],
+    ),
+    (
+        "error.internal",
+        ["服务器内部错误", "Internal server error", "サーバー内部エラー"],
+    ),
+    (
+        "error.parse",
+        [
+            "数据解析失败: {error}",
+            "Failed to parse data: {error}",
+            "データの解析に失敗しました: {error}",
+        ],
+    ),
+    (
+        "validation.failed",
+        [
+            "数据验证失败: {details}",
+            "Validation failed: {details}",
+            "データの検証に失敗しました: {details}",
+        ],
+    ),
+    (
+        "format.not_acceptable",
+        [
+            "不支持的响应格式, 可选: {accepts}",
+            "Unsupported response format, available: {accepts}",
+            "サポートされていない応答形式です。利用可能: {accepts}",
+        ],
+    ),
+    (
+        "file.not_found",
+        ["文件不存在", "File not found", "ファイルが存在しません"],
+    ),
+    (
+        "file.forbidden",
+        [
+            "无权访问文件",
+            "Access to the file is denied",
+            "ファイルへのアクセス権がありません",
+        ],
+    ),
+    (
+        "real_ip.unknown",
+        [
+            "无法获取客户端 IP",
+            "Unable to determine client IP",
+            "クライアント IP を取得できません",
+        ],
+    ),
+];
+
+/// 消息目录, 按语言存放消息模板
+///
+/// 语言标签不区分大小写, 查找顺序为完整标签, 主标签, 默认语言
+#[derive(Debug, Clone)]
+pub struct Catalog {
+    default: Arc<str>,
+    tables: HashMap<Arc<str>, HashMap<Arc<str>, Arc<str>>>,
+}
+
+impl Catalog {
+    /// 空目录
+    pub fn new(default: &str) -> Self {
+        Self {
+            default: default.to_ascii_lowercase().into(),
+            tables: HashMap::new(),
+        }
+    }
+
+    /// 内置中文, 英文, 日文消息, 默认中文
+    pub fn builtin() -> Self {
+        let mut catalog = Self::new("zh");
+        for (key, texts) in BUILTIN {
+            for (locale, text) in ["zh", "en", "ja"].into_iter().zip(texts) {
+                catalog = catalog.add(locale, key, text);
+            }
+        }
+        catalog
+    }
+
+    /// 设置默认语言
+    pub fn default_locale(mut self, locale: &str) -> Self {
+        self.default = locale.to_ascii_lowercase().into();
+        self
+    }
+
+    /// 添加或覆盖一条消息
+    pub fn add(mut self, locale: &str, key: &str, text: &str) -> Self {
+        self.tables
+            .entry(locale.to_ascii_lowercase().into())
+            .or_default()
+            .insert(key.into(), text.into());
+        self
+    }
+
+    /// 批量添加消息
+    pub fn extend<'a>(mut self, locale: &str, entries: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        for (key, text) in entries {
+            self = self.add(locale, key, text);
+        }
+        self
+    }
+
+    pub fn default_lang(&self) -> &Arc<str> {
+        &self.default
+    }
+
+    /// 已有的语言中与 tag 匹配的一项
+    fn resolve(&self, tag: &str) -> Option<&Arc<str>> {
+        let tag = tag.to_ascii_lowercase();
+        let primary = tag.split('-').next().unwrap_or_default();
+        self.tables
+            .get_key_value(tag.as_str())
+            .or_else(|| self.tables.get_key_value(primary))
+            .or_else(|| {
+                self.tables
+                    .iter()
+                    .find(|(locale, _)| locale.split('-').next() == Some(primary))
+            })
+            .map(|(locale, _)| locale)
+    }
+
+    /// 按 Accept-Language 协商语言, 无匹配时为默认语言
+    pub fn negotiate(&self, accept_language: &str) -> Arc<str> {
+        let mut ranges: Vec<(&str, f32)> = accept_language
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';').map(str::trim);
+                let tag = parts.next().filter(|t| !t.is_empty())?;
+                let q = parts
+                    .find_map(|p| p.strip_prefix("q="))
+                    .and_then(|q| q.parse().ok())
+                    .unwrap_or(1.0);
+                Some((tag, q))
+            })
+            .filter(|(_, q)| *q > 0.0)
+            .collect();
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranges
+            .iter()
+            .find_map(|(tag, _)| if *tag == "*" { None } else { self.resolve(tag) })
+            .unwrap_or(&self.default)
+            .clone()
+    }
+
+    /// 查找消息模板, 依次回退到主标签与默认语言
+    pub fn get(&self, locale: &str, key: &str) -> Option<&str> {
+        let lookup = |locale: &str| self.tables.get(locale).and_then(|table| table.get(key));
+        self.resolve(locale)
+            .and_then(|locale| lookup(locale))
+            .or_else(|| lookup(&self.default))
+            .map(|text| &**text)
+    }
+
+    /// 查找并填充参数
+    pub fn format(&self, locale: &str, key: &str, args: &[(impl AsRef<str>, impl AsRef<str>)]) -> Option<String> {
+        let mut text = self.get(locale, key)?.to_string();
+        for (name, value) in args {
+            text = text.replace(&format!("{{{}}}", name.as_ref()), value.as_ref());
+        }
+        Some(text)
+    }
+}
+
+impl Default for Catalog {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+/// 设置全局消息目录, 只能设置一次, 需在启动服务之前调用
+pub fn install(catalog: Catalog) -> Result<(), Catalog> {
+    CATALOG.set(catalog)
+}
+
+/// 全局消息目录, 未设置时使用内置消息
+pub fn catalog() -> &'static Catalog {
+    CATALOG.get_or_init(Catalog::default)
+}
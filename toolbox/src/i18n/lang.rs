@@ -0,0 +1,52 @@
+use std::{fmt, sync::Arc};
+
+use derive_more::Deref;
+use salvo::{Extractible, Request, Writer, http::header::ACCEPT_LANGUAGE};
+
+use crate::{global::METADATE, i18n::catalog::catalog, resp::Res};
+
+/// 按 Accept-Language 协商出的语言
+#[derive(Debug, Clone, Deref, PartialEq, Eq)]
+pub struct Lang(pub Arc<str>);
+
+impl Lang {
+    pub fn of(req: &Request) -> Self {
+        if let Some(lang) = req.extensions().get::<Self>() {
+            return lang.clone();
+        }
+        let accept = req
+            .headers()
+            .get(ACCEPT_LANGUAGE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default();
+        Self(catalog().negotiate(accept))
+    }
+
+    /// 翻译消息, 不存在时返回 key
+    pub fn t(&self, key: &str) -> String {
+        self.tf(key, &[] as &[(&str, &str)])
+    }
+
+    /// 翻译消息并填充参数
+    pub fn tf(&self, key: &str, args: &[(impl AsRef<str>, impl AsRef<str>)]) -> String {
+        catalog().format(&self.0, key, args).unwrap_or_else(|| key.into())
+    }
+}
+
+impl fmt::Display for Lang {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl<'ex> Extractible<'ex> for Lang {
+    fn metadata() -> &'ex salvo::extract::Metadata {
+        &METADATE
+    }
+
+    async fn extract(req: &'ex mut Request) -> Result<Self, impl Writer + Send + fmt::Debug + 'static> {
+        let lang = Self::of(req);
+        req.extensions_mut().insert(lang.clone());
+        Ok::<_, Res>(lang)
+    }
+}
@@ -0,0 +1,5 @@
+pub mod catalog;
+pub mod lang;
+
+pub use catalog::{Catalog, catalog, install};
+pub use lang::Lang;
@@ -1,7 +1,12 @@
+pub mod audit;
 pub mod auth;
 pub mod compare;
+pub mod error;
 pub mod global;
+pub mod i18n;
 pub mod logger;
 pub mod macros;
+pub mod metrics;
+pub mod real_ip;
 pub mod resp;
 pub mod validator;
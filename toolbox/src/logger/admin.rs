@@ -0,0 +1,53 @@
+use salvo::{Depot, FlowCtrl, Handler, Request, Response, Writer, async_trait};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    audit::{AuditEvent, AuditKind},
+    logger::{middleware::Logger, pipeline::SinkConfig},
+    res,
+    resp::Res,
+};
+
+/// 运行时日志配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LoggerConfig {
+    #[serde(default)]
+    pub sinks: Vec<SinkConfig>,
+    /// 慢请求阈值, 单位毫秒
+    pub slow_ms: Option<u64>,
+}
+
+/// 日志管理接口, 接收 JSON 格式的 [`LoggerConfig`]
+///
+/// ```ignore
+/// Router::with_path("admin/logger").post(LoggerAdmin::new(logger.clone()))
+/// ```
+#[derive(Clone)]
+pub struct LoggerAdmin {
+    logger: Logger,
+}
+
+impl LoggerAdmin {
+    pub fn new(logger: Logger) -> Self {
+        Self { logger }
+    }
+}
+
+#[async_trait]
+impl Handler for LoggerAdmin {
+    async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, _ctrl: &mut FlowCtrl) {
+        let result: Res = match req.parse_json::<LoggerConfig>().await {
+            Ok(config) => {
+                AuditEvent::new(AuditKind::AdminAction)
+                    .request(req, depot)
+                    .resource("logger")
+                    .detail("config", serde_json::to_value(&config).unwrap_or_default())
+                    .record();
+                self.logger.apply(config);
+                res!(200, "日志配置已更新")
+            }
+            Err(err) => err.into(),
+        };
+        result.write(req, depot, res).await;
+    }
+}
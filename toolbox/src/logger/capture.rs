@@ -0,0 +1,257 @@
+use std::fmt::Write;
+
+use percent_encoding::percent_decode_str;
+use salvo::{
+    Request, Response,
+    http::{
+        HeaderMap, ResBody,
+        header::{AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE, COOKIE, HeaderName, SET_COOKIE},
+    },
+};
+use serde_json::Value;
+
+use crate::{
+    compare::str::{CompareStr, SharedCompare, shared},
+    logger::field::Fields,
+};
+
+/// 脱敏后的替换值
+const REDACTED: &str = "***";
+
+/// 请求/响应体采集, 用于排查问题, 结果写入日志字段
+/// `request_headers`, `request_body`, `response_body`
+#[derive(Clone)]
+pub struct Capture {
+    paths: SharedCompare,
+    content_types: Vec<String>,
+    limit: usize,
+    redact_fields: Vec<String>,
+    redact_headers: Vec<HeaderName>,
+}
+
+impl Capture {
+    /// paths 返回 true 的路径才会采集
+    pub fn new(paths: impl CompareStr + Send + Sync + 'static) -> Self {
+        Self {
+            paths: shared(paths),
+            content_types: vec![
+                "application/json".into(),
+                "application/x-www-form-urlencoded".into(),
+                "text/".into(),
+            ],
+            limit: 4096,
+            redact_fields: vec!["password".into()],
+            redact_headers: vec![AUTHORIZATION, COOKIE, SET_COOKIE],
+        }
+    }
+
+    /// 采集的 Content-Type 前缀, 默认 json / form / text
+    pub fn content_types(mut self, content_types: Vec<String>) -> Self {
+        self.content_types = content_types;
+        self
+    }
+
+    /// 单个 body 写入日志的最大字节数, 默认 4096
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// 需要脱敏的 JSON 与表单字段名, 不区分大小写, 默认 password
+    pub fn redact_fields(mut self, fields: Vec<String>) -> Self {
+        self.redact_fields = fields;
+        self
+    }
+
+    /// 需要脱敏的请求头, 默认 Authorization / Cookie / Set-Cookie
+    pub fn redact_headers(mut self, headers: Vec<HeaderName>) -> Self {
+        self.redact_headers = headers;
+        self
+    }
+
+    pub fn matches(&self, path: &str) -> bool {
+        (self.paths)(path)
+    }
+
+    fn accept(&self, headers: &HeaderMap) -> bool {
+        let Some(content_type) = headers.get(CONTENT_TYPE).and_then(|v| v.to_str().ok()) else {
+            return false;
+        };
+        self.content_types.iter().any(|t| content_type.starts_with(t.as_str()))
+    }
+
+    /// 在调用后续处理器之前采集请求头与请求体
+    ///
+    /// 请求体会被缓存到 Request 中, 后续处理器仍可正常解析;
+    /// 未声明 Content-Length 或超过 secure_max_size 的请求体不采集, 避免影响后续解析
+    pub async fn request(&self, req: &mut Request, fields: &mut Fields) {
+        let mut headers = String::new();
+        for (name, value) in req.headers() {
+            let value = match self.redact_headers.contains(name) {
+                true => REDACTED.into(),
+                false => String::from_utf8_lossy(value.as_bytes()),
+            };
+            write!(headers, "{name}: {value}; ").ok();
+        }
+        fields.push("request_headers".into(), headers.trim_end().into());
+
+        if !self.accept(req.headers()) {
+            return;
+        }
+        let length = req
+            .headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<usize>().ok());
+        if length.is_none_or(|n| n > req.secure_max_size()) {
+            return;
+        }
+        let kind = BodyKind::of(req.headers());
+        if let Ok(bytes) = req.payload().await {
+            fields.push("request_body".into(), self.render(kind, bytes));
+        }
+    }
+
+    /// 采集一次性写入的响应体, 流式响应不采集
+    pub fn response(&self, res: &Response, fields: &mut Fields) {
+        if !self.accept(res.headers()) {
+            return;
+        }
+        if let ResBody::Once(bytes) = &res.body {
+            fields.push("response_body".into(), self.render(BodyKind::of(res.headers()), bytes));
+        }
+    }
+
+    /// JSON 与表单脱敏后按 limit 截断, 无法解析时不输出内容, 避免泄露未脱敏的字段
+    fn render(&self, kind: BodyKind, bytes: &[u8]) -> String {
+        let mut body = match kind {
+            BodyKind::Json => match serde_json::from_slice::<Value>(bytes) {
+                Ok(mut value) => {
+                    self.redact(&mut value);
+                    value.to_string()
+                }
+                Err(_) => return unparsed("json", bytes),
+            },
+            BodyKind::Form => match std::str::from_utf8(bytes) {
+                Ok(form) => self.redact_form(form),
+                Err(_) => return unparsed("form", bytes),
+            },
+            BodyKind::Text => String::from_utf8_lossy(bytes).into_owned(),
+        };
+        if body.len() > self.limit {
+            let mut end = self.limit;
+            while !body.is_char_boundary(end) {
+                end -= 1;
+            }
+            let total = body.len();
+            body.truncate(end);
+            write!(body, "...({total} bytes)").ok();
+        }
+        body
+    }
+
+    fn redact(&self, value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map.iter_mut() {
+                    if self.redact_fields.iter().any(|f| f.eq_ignore_ascii_case(key)) {
+                        *value = Value::String(REDACTED.into());
+                    } else {
+                        self.redact(value);
+                    }
+                }
+            }
+            Value::Array(list) => list.iter_mut().for_each(|v| self.redact(v)),
+            _ => {}
+        }
+    }
+
+    /// 替换 `application/x-www-form-urlencoded` 中需要脱敏的字段值
+    fn redact_form(&self, form: &str) -> String {
+        let pairs: Vec<_> = form
+            .split('&')
+            .map(|pair| {
+                let (key, _) = pair.split_once('=').unwrap_or((pair, ""));
+                let name = key.replace('+', " ");
+                let name = percent_decode_str(&name).decode_utf8_lossy();
+                if self.redact_fields.iter().any(|f| f.eq_ignore_ascii_case(&name)) {
+                    format!("{key}={REDACTED}")
+                } else {
+                    pair.to_string()
+                }
+            })
+            .collect();
+        pairs.join("&")
+    }
+}
+
+/// 按 Content-Type 区分的 body 类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BodyKind {
+    Json,
+    Form,
+    Text,
+}
+
+impl BodyKind {
+    fn of(headers: &HeaderMap) -> Self {
+        let content_type = headers
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if content_type.contains("json") {
+            Self::Json
+        } else if content_type.starts_with("application/x-www-form-urlencoded") {
+            Self::Form
+        } else {
+            Self::Text
+        }
+    }
+}
+
+/// 无法解析的 body 只记录类型与大小
+fn unparsed(kind: &str, bytes: &[u8]) -> String {
+    format!("<unparsed {kind}, {} bytes>", bytes.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capture() -> Capture {
+        Capture::new(|_: &str| true).redact_fields(vec!["password".into(), "token".into()])
+    }
+
+    #[test]
+    fn redact_json() {
+        let body = br#"{"user":"a","Password":"p","list":[{"token":"t"}]}"#;
+        let body = capture().render(BodyKind::Json, body);
+        let expected = serde_json::json!({"user": "a", "Password": "***", "list": [{"token": "***"}]});
+        assert_eq!(serde_json::from_str::<Value>(&body).unwrap(), expected);
+    }
+
+    #[test]
+    fn redact_form() {
+        let body = capture().render(BodyKind::Form, b"user=a&pass%77ord=p%26q&token&x=1");
+        assert_eq!(body, "user=a&pass%77ord=***&token=***&x=1");
+    }
+
+    #[test]
+    fn invalid_body_is_dropped() {
+        let body = capture().render(BodyKind::Json, br#"{"password":"p"#);
+        assert_eq!(body, "<unparsed json, 14 bytes>");
+        let body = capture().render(BodyKind::Form, b"password=\xff");
+        assert_eq!(body, "<unparsed form, 10 bytes>");
+    }
+
+    #[test]
+    fn truncate_after_redaction() {
+        let body = capture()
+            .limit(20)
+            .render(BodyKind::Json, br#"{"password":"0123456789"}"#);
+        assert_eq!(body, r#"{"password":"***"}"#);
+        let body = capture().limit(4).render(BodyKind::Text, "日志abc".as_bytes());
+        assert_eq!(body, "日...(9 bytes)");
+    }
+}
@@ -0,0 +1,191 @@
+use std::sync::{Arc, OnceLock};
+
+use time::{
+    Duration, OffsetDateTime, UtcOffset,
+    format_description::{self, BorrowedFormatItem, OwnedFormatItem},
+    macros::format_description,
+};
+
+/// 默认时间格式
+pub const DEFAULT_FORMAT: &[BorrowedFormatItem<'_>] =
+    format_description!("[year repr:last_two]-[month]-[day] [hour]:[minute]:[second].[subsecond digits:4]");
+
+static CLOCK: OnceLock<Clock> = OnceLock::new();
+
+/// 时区
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeZone {
+    Utc,
+    Fixed(UtcOffset),
+    /// 本地时区, 无法获取时使用 fallback
+    ///
+    /// 多线程环境下 Linux 可能无法获取本地时区, 创建 Clock 时会预先计算未来一年的偏移量 (含夏令时切换),
+    /// 建议在启动 tokio 运行时之前创建 Clock
+    Local {
+        fallback: UtcOffset,
+    },
+}
+
+/// 预先计算偏移量的时长
+const LOCAL_SPAN: Duration = Duration::days(366);
+
+/// 偏移量变化表, 按时间升序排列的 (unix 时间戳, 偏移量)
+type Offsets = Arc<[(i64, UtcOffset)]>;
+
+impl TimeZone {
+    /// 计算 from 起 LOCAL_SPAN 内的偏移量变化
+    fn offsets(&self, from: OffsetDateTime) -> Offsets {
+        let fallback = match self {
+            Self::Utc => return Arc::new([(i64::MIN, UtcOffset::UTC)]),
+            Self::Fixed(offset) => return Arc::new([(i64::MIN, *offset)]),
+            Self::Local { fallback } => *fallback,
+        };
+        let at = |time: OffsetDateTime| UtcOffset::local_offset_at(time).ok();
+        let Some(mut offset) = at(from) else {
+            return Arc::new([(i64::MIN, fallback)]);
+        };
+        let mut changes = vec![(i64::MIN, offset)];
+        // 按天采样, 发现变化后在当天内按 15 分钟查找切换时间
+        let mut day = from;
+        while day - from < LOCAL_SPAN {
+            let next = day + Duration::DAY;
+            if at(next).is_some_and(|o| o != offset) {
+                let mut time = day;
+                while time < next {
+                    time += Duration::minutes(15);
+                    if let Some(o) = at(time)
+                        && o != offset
+                    {
+                        changes.push((time.unix_timestamp(), o));
+                        offset = o;
+                    }
+                }
+            }
+            day = next;
+        }
+        changes.into()
+    }
+}
+
+/// 日志时钟, 决定时间来源, 时区与时间格式
+#[derive(Debug, Clone)]
+pub struct Clock {
+    source: fn() -> OffsetDateTime,
+    zone: TimeZone,
+    offsets: Offsets,
+    /// offsets 覆盖的截止时间, 之后尝试重新获取本地偏移量
+    until: i64,
+    format: Option<OwnedFormatItem>,
+}
+
+impl Clock {
+    pub fn new(zone: TimeZone) -> Self {
+        let now = OffsetDateTime::now_utc();
+        Self {
+            source: OffsetDateTime::now_utc,
+            zone,
+            offsets: zone.offsets(now),
+            until: (now + LOCAL_SPAN).unix_timestamp(),
+            format: None,
+        }
+    }
+
+    /// 本地时区, 无法获取时回退到 UTC
+    pub fn local() -> Self {
+        Self::new(TimeZone::Local {
+            fallback: UtcOffset::UTC,
+        })
+    }
+
+    /// 设置时间来源, 默认 `OffsetDateTime::now_utc`
+    pub fn source(mut self, source: fn() -> OffsetDateTime) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// 设置时间格式, 语法见 time 的 format_description
+    pub fn format(mut self, format: &str) -> Result<Self, time::error::InvalidFormatDescription> {
+        self.format = Some(format_description::parse_owned::<2>(format)?);
+        Ok(self)
+    }
+
+    pub fn zone(&self) -> TimeZone {
+        self.zone
+    }
+
+    /// 当前偏移量
+    pub fn offset(&self) -> UtcOffset {
+        self.offset_at((self.source)())
+    }
+
+    /// 指定时间的偏移量, 超出预先计算的范围时重新获取本地偏移量
+    pub fn offset_at(&self, time: OffsetDateTime) -> UtcOffset {
+        let timestamp = time.unix_timestamp();
+        if timestamp >= self.until
+            && let TimeZone::Local { .. } = self.zone
+            && let Ok(offset) = UtcOffset::local_offset_at(time)
+        {
+            return offset;
+        }
+        let i = self.offsets.partition_point(|(start, _)| *start <= timestamp);
+        self.offsets[i.saturating_sub(1)].1
+    }
+
+    pub fn now(&self) -> OffsetDateTime {
+        let now = (self.source)();
+        now.to_offset(self.offset_at(now))
+    }
+
+    pub fn display(&self, time: &OffsetDateTime) -> String {
+        let result = match &self.format {
+            Some(format) => time.format(format),
+            None => time.format(DEFAULT_FORMAT),
+        };
+        result.unwrap_or_else(|_| time.to_string())
+    }
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self::local()
+    }
+}
+
+/// 设置全局时钟, 只能设置一次, 需在创建 Logger 等调用 [`clock`] 的组件之前调用
+///
+/// 已设置或已使用默认时钟时忽略并输出警告, 单个 Logger 可使用 [`Logger::clock`](crate::logger::Logger::clock) 设置
+pub fn install(clock: Clock) -> Result<(), Clock> {
+    CLOCK.set(clock).inspect_err(|_| {
+        eprintln!("全局时钟已初始化, 本次 install 被忽略, 请在创建 Logger 之前调用");
+    })
+}
+
+/// 全局时钟, 未设置时使用本地时区并回退到 UTC, 用于审计日志, 响应时间戳与未指定时钟的 Logger
+pub fn clock() -> &'static Clock {
+    CLOCK.get_or_init(Clock::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::{datetime, offset};
+
+    use super::*;
+
+    #[test]
+    fn offset_changes() {
+        let mut clock = Clock::new(TimeZone::Fixed(offset!(+8)));
+        assert_eq!(clock.offset(), offset!(+8));
+
+        // 模拟夏令时切换
+        let start = datetime!(2024-03-31 01:00 UTC).unix_timestamp();
+        let end = datetime!(2024-10-27 01:00 UTC).unix_timestamp();
+        clock.offsets = Arc::new([(i64::MIN, offset!(+1)), (start, offset!(+2)), (end, offset!(+1))]);
+        assert_eq!(clock.offset_at(datetime!(2024-03-31 00:59 UTC)), offset!(+1));
+        assert_eq!(clock.offset_at(datetime!(2024-03-31 01:00 UTC)), offset!(+2));
+        assert_eq!(clock.offset_at(datetime!(2024-10-27 00:59 UTC)), offset!(+2));
+        assert_eq!(clock.offset_at(datetime!(2024-12-01 00:00 UTC)), offset!(+1));
+
+        clock = clock.source(|| datetime!(2024-06-01 12:00 UTC));
+        assert_eq!(clock.now(), datetime!(2024-06-01 14:00 +2));
+    }
+}
@@ -0,0 +1,150 @@
+use std::{
+    fmt::{self, Write as _},
+    io::{self, Write},
+    sync::Arc,
+};
+
+use crossbeam_channel::Sender;
+use serde_json::{Value, json};
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+use tracing::{
+    Level, Subscriber,
+    field::{Field, Visit},
+};
+use tracing_subscriber::{Layer, layer::Context};
+
+use crate::logger::{
+    clock::Clock,
+    message::Record,
+    theme::{RESET, Theme},
+};
+
+/// 访问日志转发为 tracing 事件时使用的 target, LoggerLayer 会忽略该 target 防止循环
+pub const ACCESS_TARGET: &str = "toolbox::access";
+
+/// 审计日志转发为 tracing 事件时使用的 target
+pub const AUDIT_TARGET: &str = "toolbox::audit";
+
+/// 应用 tracing 事件
+pub struct Event {
+    pub time: OffsetDateTime,
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+    pub fields: String,
+    /// Logger 的时钟, 决定时间格式
+    pub clock: Arc<Clock>,
+}
+
+impl Event {
+    /// 转为 JSON, 用于结构化输出
+    pub fn to_json(&self) -> Value {
+        json!({
+            "time": self.time.format(&Rfc3339).ok(),
+            "level": self.level.as_str(),
+            "target": self.target,
+            "message": self.message,
+            "fields": self.fields.trim(),
+        })
+    }
+
+    fn format(&self) -> String {
+        self.clock.display(&self.time)
+    }
+
+    pub fn write(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "[{}] ", self.format())?;
+        self.write_plain(out)
+    }
+
+    /// 不带时间前缀, 用于自带时间戳的输出
+    pub fn write_plain(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "{:>5} │ ", self.level)?;
+        write!(out, "{} │ ", self.target)?;
+        writeln!(out, "{}{}", self.message, self.fields)
+    }
+
+    /// 使用默认配色输出
+    pub fn write_color(&self, out: &mut impl Write) -> io::Result<()> {
+        self.write_themed(out, Theme::default_ref())
+    }
+
+    pub fn write_themed(&self, out: &mut impl Write, theme: &Theme) -> io::Result<()> {
+        let level = theme.level_color(self.level);
+        write!(out, "[{}] ", self.format())?; // 时间
+        write!(out, "{level}{:>5}{RESET} │ ", self.level)?; // 等级
+        write!(out, "{}{}{RESET} │ ", theme.target, self.target)?; // 来源
+        writeln!(out, "{}{}{}{RESET}", self.message, theme.fields, self.fields) // 内容
+    }
+}
+
+/// 收集事件字段, message 单独存放, 其余字段拼接为 ` key=value`
+#[derive(Default)]
+struct EventVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for EventVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            write!(self.fields, " {}={value}", field.name()).ok();
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            write!(self.message, "{value:?}").ok();
+        } else {
+            write!(self.fields, " {}={value:?}", field.name()).ok();
+        }
+    }
+}
+
+/// 将应用 tracing 事件转发到 Logger 的输出管道
+///
+/// ```ignore
+/// use tracing_subscriber::prelude::*;
+///
+/// let logger = Logger::default();
+/// tracing_subscriber::registry().with(logger.layer()).init();
+/// ```
+pub struct LoggerLayer {
+    pub(crate) sender: Sender<Record>,
+    pub(crate) level: Level,
+    pub(crate) clock: Arc<Clock>,
+}
+
+impl LoggerLayer {
+    /// 设置最低输出等级, 默认 INFO
+    pub fn level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+}
+
+impl<S: Subscriber> Layer<S> for LoggerLayer {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+        let meta = event.metadata();
+        if *meta.level() > self.level || meta.target() == ACCESS_TARGET {
+            return;
+        }
+
+        let mut visitor = EventVisitor::default();
+        event.record(&mut visitor);
+        let event = Event {
+            time: self.clock.now(),
+            level: *meta.level(),
+            target: meta.target().to_string(),
+            message: visitor.message,
+            fields: visitor.fields,
+            clock: self.clock.clone(),
+        };
+
+        if let Err(err) = self.sender.send(Record::Event(event)) {
+            eprintln!("Send 日志时出现错误 {err}")
+        }
+    }
+}
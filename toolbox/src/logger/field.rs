@@ -0,0 +1,138 @@
+use std::{fmt::Write, sync::Arc};
+
+use salvo::{
+    Depot, Request, Response,
+    http::{
+        Version,
+        header::{CONTENT_LENGTH, HeaderName, REFERER, USER_AGENT},
+    },
+};
+
+/// 用户 ID 在 Depot 中的 key, 值类型为 `Arc<str>`
+pub const USER_ID: &str = "user_id";
+
+/// 可选的访问日志字段
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Field {
+    /// 请求头 User-Agent
+    UserAgent,
+    /// 请求头 Referer
+    Referer,
+    /// 请求体大小, 取自 Content-Length
+    RequestSize,
+    /// 响应体大小
+    ResponseSize,
+    /// 查询字符串, 含前导 `?`, 无查询参数时不存在
+    Query,
+    /// HTTP 版本
+    Version,
+    /// 已认证用户 ID, 取自 Depot 中的 [`USER_ID`]
+    UserId,
+    /// 指定请求头
+    RequestHeader(HeaderName),
+    /// 指定响应头
+    ResponseHeader(HeaderName),
+    /// Depot 中类型为 `Arc<str>` 的值
+    Depot(&'static str),
+}
+
+impl Field {
+    /// 字段名, 同时作为模板占位符名称
+    pub fn name(&self) -> Arc<str> {
+        match self {
+            Self::UserAgent => "user_agent".into(),
+            Self::Referer => "referer".into(),
+            Self::RequestSize => "request_size".into(),
+            Self::ResponseSize => "response_size".into(),
+            Self::Query => "query".into(),
+            Self::Version => "version".into(),
+            Self::UserId => "user_id".into(),
+            Self::RequestHeader(name) => format!("http_{}", name.as_str().replace('-', "_")).into(),
+            Self::ResponseHeader(name) => format!("sent_http_{}", name.as_str().replace('-', "_")).into(),
+            Self::Depot(key) => (*key).into(),
+        }
+    }
+
+    /// 按模板占位符名称解析字段, 与 [`Field::name`] 相反, Depot 字段无法解析
+    pub fn from_name(name: &str) -> Option<Self> {
+        let header = |name: &str| HeaderName::from_bytes(name.replace('_', "-").as_bytes()).ok();
+        match name {
+            "user_agent" => Some(Self::UserAgent),
+            "referer" => Some(Self::Referer),
+            "request_size" => Some(Self::RequestSize),
+            "response_size" => Some(Self::ResponseSize),
+            "query" => Some(Self::Query),
+            "version" => Some(Self::Version),
+            "user_id" => Some(Self::UserId),
+            _ => {
+                if let Some(name) = name.strip_prefix("sent_http_") {
+                    header(name).map(Self::ResponseHeader)
+                } else {
+                    header(name.strip_prefix("http_")?).map(Self::RequestHeader)
+                }
+            }
+        }
+    }
+
+    /// 采集字段值, 不存在时为 None
+    pub fn collect(&self, req: &Request, depot: &Depot, res: &Response) -> Option<String> {
+        let header = |name: &HeaderName| {
+            req.headers()
+                .get(name)
+                .map(|v| String::from_utf8_lossy(v.as_bytes()).into_owned())
+        };
+        match self {
+            Self::UserAgent => header(&USER_AGENT),
+            Self::Referer => header(&REFERER),
+            Self::RequestSize => header(&CONTENT_LENGTH),
+            Self::ResponseSize => res.body.size().map(|n| n.to_string()),
+            Self::Query => req.uri().query().filter(|q| !q.is_empty()).map(|q| format!("?{q}")),
+            Self::Version => Some(version(req.version()).into()),
+            Self::UserId => depot.get::<Arc<str>>(USER_ID).ok().map(|v| v.to_string()),
+            Self::RequestHeader(name) => header(name),
+            Self::ResponseHeader(name) => res
+                .headers()
+                .get(name)
+                .map(|v| String::from_utf8_lossy(v.as_bytes()).into_owned()),
+            Self::Depot(key) => depot.get::<Arc<str>>(key).ok().map(|v| v.to_string()),
+        }
+    }
+}
+
+fn version(version: Version) -> &'static str {
+    match version {
+        Version::HTTP_09 => "HTTP/0.9",
+        Version::HTTP_10 => "HTTP/1.0",
+        Version::HTTP_11 => "HTTP/1.1",
+        Version::HTTP_2 => "HTTP/2.0",
+        Version::HTTP_3 => "HTTP/3.0",
+        _ => "HTTP/?",
+    }
+}
+
+/// 已采集的字段值, 按 Logger 中配置的顺序排列
+#[derive(Debug, Default, Clone)]
+pub struct Fields(pub Vec<(Arc<str>, String)>);
+
+impl Fields {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0.iter().find(|(k, _)| &**k == name).map(|(_, v)| v.as_str())
+    }
+
+    pub fn push(&mut self, name: Arc<str>, value: String) {
+        self.0.push((name, value));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// 拼接为 ` key=value` 形式
+    pub fn to_kv(&self) -> String {
+        let mut out = String::new();
+        for (k, v) in &self.0 {
+            write!(out, " {k}={v:?}").ok();
+        }
+        out
+    }
+}
@@ -0,0 +1,175 @@
+use std::{
+    ops::RangeInclusive,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    compare::str::{CompareStr, SharedCompare, shared},
+    logger::message::Message,
+};
+
+/// 访问日志过滤与采样规则
+///
+/// 判断顺序: 错误与慢请求始终记录 -> 路径/方法/状态码/耗时过滤 -> 按比例采样
+pub struct Filter {
+    skip: Option<SharedCompare>,
+    methods: Vec<String>,
+    status: Vec<RangeInclusive<u16>>,
+    min_elapsed: Option<Duration>,
+    sample: f64,
+    always_errors: bool,
+    slow: Option<Duration>,
+    seed: u64,
+}
+
+impl Filter {
+    pub fn new() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+        Self {
+            skip: None,
+            methods: Vec::new(),
+            status: Vec::new(),
+            min_elapsed: None,
+            sample: 1.0,
+            always_errors: true,
+            slow: None,
+            seed: seed | 1,
+        }
+    }
+
+    /// 跳过 paths 返回 true 的路径, 如健康检查与静态资源
+    pub fn skip(mut self, paths: impl CompareStr + Send + Sync + 'static) -> Self {
+        self.skip = Some(shared(paths));
+        self
+    }
+
+    /// 仅记录指定请求方法
+    pub fn methods(mut self, methods: &[&str]) -> Self {
+        self.methods = methods.iter().map(|m| m.to_ascii_uppercase()).collect();
+        self
+    }
+
+    /// 仅记录指定状态码区间, 可多次调用
+    pub fn status(mut self, range: RangeInclusive<u16>) -> Self {
+        self.status.push(range);
+        self
+    }
+
+    /// 仅记录耗时不低于 elapsed 的请求
+    pub fn min_elapsed(mut self, elapsed: Duration) -> Self {
+        self.min_elapsed = Some(elapsed);
+        self
+    }
+
+    /// 采样比例 0.0 ~ 1.0, 默认 1.0 全部记录
+    pub fn sample(mut self, rate: f64) -> Self {
+        self.sample = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// 5xx 是否始终记录, 默认 true
+    pub fn always_errors(mut self, always: bool) -> Self {
+        self.always_errors = always;
+        self
+    }
+
+    /// 耗时不低于 elapsed 的请求始终记录
+    pub fn slow(mut self, elapsed: Duration) -> Self {
+        self.slow = Some(elapsed);
+        self
+    }
+
+    pub fn allow(&mut self, message: &Message) -> bool {
+        let elapsed = message.elapsed.unsigned_abs();
+        if self.always_errors && message.status >= 500 {
+            return true;
+        }
+        if self.slow.is_some_and(|slow| elapsed >= slow) {
+            return true;
+        }
+
+        if let Some(skip) = &self.skip {
+            // 重定向时 path 带有 ` -> 目标地址`
+            let path = message.path.split(" -> ").next().unwrap_or_default();
+            if skip(path) {
+                return false;
+            }
+        }
+        if !self.methods.is_empty() && !self.methods.contains(&message.method) {
+            return false;
+        }
+        if !self.status.is_empty() && !self.status.iter().any(|r| r.contains(&message.status)) {
+            return false;
+        }
+        if self.min_elapsed.is_some_and(|min| elapsed < min) {
+            return false;
+        }
+
+        self.sample >= 1.0 || self.random() < self.sample
+    }
+
+    /// xorshift64*, 只在日志线程内使用, 无需加密强度
+    fn random(&mut self) -> f64 {
+        self.seed ^= self.seed >> 12;
+        self.seed ^= self.seed << 25;
+        self.seed ^= self.seed >> 27;
+        let n = self.seed.wrapping_mul(0x2545_F491_4F6C_DD1D);
+        (n >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 可序列化的过滤规则, 用于管理接口与配置重载, 未设置的字段使用 [`Filter`] 的默认值
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FilterConfig {
+    /// 跳过包含其中任一片段的路径
+    #[serde(default)]
+    pub skip: Vec<String>,
+    #[serde(default)]
+    pub methods: Vec<String>,
+    /// 状态码区间, 如 `[[200, 299]]`
+    #[serde(default)]
+    pub status: Vec<(u16, u16)>,
+    pub min_elapsed_ms: Option<u64>,
+    pub sample: Option<f64>,
+    pub always_errors: Option<bool>,
+    pub slow_ms: Option<u64>,
+}
+
+impl From<&FilterConfig> for Filter {
+    fn from(config: &FilterConfig) -> Self {
+        let mut filter = Filter::new();
+        if !config.skip.is_empty() {
+            filter = filter.skip(Arc::new(config.skip.clone()));
+        }
+        let methods: Vec<&str> = config.methods.iter().map(String::as_str).collect();
+        filter = filter.methods(&methods);
+        for (start, end) in &config.status {
+            filter = filter.status(*start..=*end);
+        }
+        if let Some(ms) = config.min_elapsed_ms {
+            filter = filter.min_elapsed(Duration::from_millis(ms));
+        }
+        if let Some(rate) = config.sample {
+            filter = filter.sample(rate);
+        }
+        if let Some(always) = config.always_errors {
+            filter = filter.always_errors(always);
+        }
+        if let Some(ms) = config.slow_ms {
+            filter = filter.slow(Duration::from_millis(ms));
+        }
+        filter
+    }
+}
@@ -0,0 +1,216 @@
+use std::{
+    io,
+    ops::RangeInclusive,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use crate::logger::{
+    message::Message,
+    output::{OutFile, Output, OutputMethod},
+};
+
+/// 告警回调, 在日志线程中执行, 不应长时间阻塞
+pub trait Hook {
+    fn on_message(&mut self, message: &Message);
+}
+
+impl<F: FnMut(&Message)> Hook for F {
+    fn on_message(&mut self, message: &Message) {
+        self(message)
+    }
+}
+
+/// 告警触发条件
+pub enum Condition {
+    /// 耗时不低于指定时间
+    Slow(Duration),
+    /// 5xx
+    ServerError,
+    /// 状态码区间
+    Status(RangeInclusive<u16>),
+    /// 任一条件满足
+    Any(Vec<Condition>),
+    /// 自定义判断
+    Custom(fn(&Message) -> bool),
+}
+
+impl Condition {
+    pub fn matches(&self, message: &Message) -> bool {
+        match self {
+            Self::Slow(elapsed) => message.elapsed.unsigned_abs() >= *elapsed,
+            Self::ServerError => message.status >= 500,
+            Self::Status(range) => range.contains(&message.status),
+            Self::Any(list) => list.iter().any(|c| c.matches(message)),
+            Self::Custom(f) => f(message),
+        }
+    }
+}
+
+/// 条件与回调
+pub struct Alert {
+    pub condition: Condition,
+    pub hook: Box<dyn Hook + Send>,
+}
+
+impl Alert {
+    pub fn new(condition: Condition, hook: impl Hook + Send + 'static) -> Self {
+        Self {
+            condition,
+            hook: Box::new(hook),
+        }
+    }
+
+    pub(crate) fn check(&mut self, message: &Message) {
+        if self.condition.matches(message) {
+            self.hook.on_message(message);
+        }
+    }
+}
+
+/// 将触发告警的访问日志写入单独的输出, 如 slow.log / error.log
+pub struct OutputHook(pub OutputMethod);
+
+impl OutputHook {
+    /// 写入 path 目录下的 name 文件, name 支持 `{date}`
+    pub fn file(path: impl Into<PathBuf>, name: impl Into<String>) -> io::Result<Self> {
+        OutFile::new(path.into(), name.into(), None).map(|file| Self(OutputMethod::OutputFile(file)))
+    }
+}
+
+impl Hook for OutputHook {
+    fn on_message(&mut self, message: &Message) {
+        if let Err(err) = self.0.output(message) {
+            eprintln!("输出告警日志失败: {err}");
+        }
+    }
+}
+
+/// 窗口聚合, 同一窗口内触发次数达到 threshold 时只调用一次内部回调
+///
+/// 内部回调收到的是达到阈值时的那条日志, 窗口结束后重新计数
+pub struct Window<H> {
+    pub window: Duration,
+    pub threshold: u32,
+    pub hook: H,
+    count: u32,
+    start: Instant,
+    fired: bool,
+}
+
+impl<H: Hook> Window<H> {
+    pub fn new(window: Duration, threshold: u32, hook: H) -> Self {
+        Self {
+            window,
+            threshold: threshold.max(1),
+            hook,
+            count: 0,
+            start: Instant::now(),
+            fired: false,
+        }
+    }
+
+    /// 当前窗口内的触发次数
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+}
+
+impl<H: Hook> Hook for Window<H> {
+    fn on_message(&mut self, message: &Message) {
+        if self.start.elapsed() >= self.window {
+            self.start = Instant::now();
+            self.count = 0;
+            self.fired = false;
+        }
+        self.count += 1;
+        if !self.fired && self.count >= self.threshold {
+            self.fired = true;
+            self.hook.on_message(message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        sync::{
+            Arc,
+            atomic::{AtomicU32, Ordering},
+        },
+        thread,
+    };
+
+    use super::*;
+    use crate::logger::{
+        clock::{Clock, clock},
+        field::Fields,
+        level::Level,
+    };
+
+    fn message(status: u16, elapsed_ms: i64) -> Message {
+        Message {
+            begin: clock().now(),
+            elapsed: time::Duration::milliseconds(elapsed_ms),
+            method: "GET".into(),
+            path: "/orders".into(),
+            status,
+            ip: "10.0.0.1".into(),
+            other: String::new(),
+            request_id: None,
+            fields: Fields::default(),
+            implied: Fields::default(),
+            level: Level::Info,
+            clock: Arc::new(Clock::default()),
+        }
+    }
+
+    #[test]
+    fn conditions() {
+        let condition = Condition::Any(vec![
+            Condition::ServerError,
+            Condition::Slow(Duration::from_millis(500)),
+        ]);
+        assert!(condition.matches(&message(502, 1)));
+        assert!(condition.matches(&message(200, 500)));
+        assert!(!condition.matches(&message(404, 1)));
+        assert!(Condition::Status(400..=499).matches(&message(404, 1)));
+    }
+
+    #[test]
+    fn window_fires_once_per_burst() {
+        let fired = Arc::new(AtomicU32::new(0));
+        let counter = fired.clone();
+        let window = Window::new(Duration::from_millis(100), 3, move |_: &Message| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        });
+        let mut alert = Alert::new(Condition::ServerError, window);
+
+        for _ in 0..10 {
+            alert.check(&message(500, 1));
+        }
+        alert.check(&message(200, 1));
+        assert_eq!(fired.load(Ordering::Relaxed), 1);
+
+        // 窗口结束后重新计数
+        thread::sleep(Duration::from_millis(150));
+        for _ in 0..2 {
+            alert.check(&message(500, 1));
+        }
+        assert_eq!(fired.load(Ordering::Relaxed), 1);
+        alert.check(&message(500, 1));
+        assert_eq!(fired.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn output_hook() {
+        let dir = std::env::temp_dir().join(format!("toolbox-hook-{}", std::process::id()));
+        let mut hook = OutputHook::file(&dir, "slow.log").unwrap();
+        hook.on_message(&message(200, 1500));
+        let content = fs::read_to_string(dir.join("slow.log")).unwrap();
+        assert!(content.contains("/orders"), "{content}");
+        assert_eq!(content.lines().count(), 1);
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,151 @@
+use std::{io, os::unix::net::UnixDatagram, path::PathBuf};
+
+use crate::{
+    audit::AuditEntry,
+    logger::{event::Event, message::Message, output::Output, syslog::Severity},
+};
+
+/// journald 原生协议输出
+///
+/// 通过 Unix 数据报发送, 单条日志超过套接字缓冲区时会发送失败 (未实现 memfd 传输)
+pub struct Journald {
+    pub path: PathBuf,
+    pub identifier: String,
+    socket: Option<UnixDatagram>,
+}
+
+impl Journald {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            identifier: "salvo".into(),
+            socket: None,
+        }
+    }
+
+    /// SYSLOG_IDENTIFIER, 默认 salvo
+    pub fn identifier(mut self, identifier: impl Into<String>) -> Self {
+        self.identifier = identifier.into();
+        self
+    }
+
+    fn send(&mut self, fields: &[(&str, &str)]) -> io::Result<()> {
+        let mut buf = Vec::with_capacity(256);
+        append(&mut buf, "SYSLOG_IDENTIFIER", &self.identifier);
+        for (key, value) in fields {
+            append(&mut buf, key, value);
+        }
+
+        let socket = match &mut self.socket {
+            Some(socket) => socket,
+            None => self.socket.insert(UnixDatagram::unbound()?),
+        };
+        socket.send_to(&buf, &self.path).map(|_| ())
+    }
+}
+
+impl Default for Journald {
+    fn default() -> Self {
+        Self::new("/run/systemd/journal/socket")
+    }
+}
+
+/// 追加字段, 值含换行时使用二进制格式 `KEY\n<u64 le 长度><值>\n`
+fn append(buf: &mut Vec<u8>, key: &str, value: &str) {
+    // 字段名只允许大写字母, 数字与下划线
+    let key: String = key
+        .chars()
+        .map(|c| match c {
+            'a'..='z' => c.to_ascii_uppercase(),
+            'A'..='Z' | '0'..='9' | '_' => c,
+            _ => '_',
+        })
+        .collect();
+    buf.extend_from_slice(key.trim_start_matches('_').as_bytes());
+    if value.contains('\n') {
+        buf.push(b'\n');
+        buf.extend_from_slice(&(value.len() as u64).to_le_bytes());
+    } else {
+        buf.push(b'=');
+    }
+    buf.extend_from_slice(value.as_bytes());
+    buf.push(b'\n');
+}
+
+impl Output for Journald {
+    fn output(&mut self, message: &Message) -> io::Result<()> {
+        let mut line = Vec::new();
+        message.write_plain(&mut line)?;
+        let line = String::from_utf8_lossy(&line);
+        let priority = (Severity::from_status(message.status) as u8).to_string();
+        let status = message.status.to_string();
+        let elapsed = format!("{:.3}", message.elapsed.as_seconds_f64() * 1000.0);
+        let request_id = message.request_id.as_ref().map(|id| &*id.0).unwrap_or_default();
+
+        let mut fields = vec![
+            ("MESSAGE", line.trim_end()),
+            ("PRIORITY", &priority),
+            ("HTTP_STATUS", &status),
+            ("HTTP_METHOD", &message.method),
+            ("HTTP_PATH", &message.path),
+            ("HTTP_ELAPSED_MS", &elapsed),
+            ("CLIENT_IP", &message.ip),
+        ];
+        if !request_id.is_empty() {
+            fields.push(("REQUEST_ID", request_id));
+        }
+        for (key, value) in &message.fields.0 {
+            fields.push((key, value));
+        }
+        self.send(&fields)
+    }
+
+    fn event(&mut self, event: &Event) -> io::Result<()> {
+        let message = format!("{}{}", event.message, event.fields);
+        let priority = (Severity::from_level(event.level) as u8).to_string();
+        self.send(&[
+            ("MESSAGE", &message),
+            ("PRIORITY", &priority),
+            ("TARGET", &event.target),
+        ])
+    }
+
+    fn audit(&mut self, entry: &AuditEntry) -> io::Result<()> {
+        let priority = (Severity::Notice as u8).to_string();
+        let seq = entry.seq.to_string();
+        self.send(&[
+            ("MESSAGE", entry.line()),
+            ("PRIORITY", &priority),
+            ("AUDIT_KIND", entry.event.kind.as_str()),
+            ("AUDIT_SEQ", &seq),
+            ("AUDIT_HASH", &entry.hash),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn native_protocol_round_trip() {
+        let path = std::env::temp_dir().join(format!("toolbox-journald-{}.sock", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let server = UnixDatagram::bind(&path).unwrap();
+        let mut journald = Journald::new(&path).identifier("app");
+
+        journald
+            .send(&[("MESSAGE", "a\nb"), ("http-status", "200"), ("_HIDDEN", "x")])
+            .unwrap();
+        let mut buf = [0; 512];
+        let n = server.recv(&mut buf).unwrap();
+
+        let mut expected = b"SYSLOG_IDENTIFIER=app\nMESSAGE\n".to_vec();
+        expected.extend_from_slice(&3u64.to_le_bytes());
+        expected.extend_from_slice(b"a\nb\nHTTP_STATUS=200\nHIDDEN=x\n");
+        assert_eq!(&buf[..n], &expected[..]);
+        fs::remove_file(&path).ok();
+    }
+}
@@ -1,31 +1,30 @@
-use std::io::{self, Write};
+use std::{
+    io::{self, Write},
+    sync::Arc,
+};
 
-use time::{OffsetDateTime, format_description::BorrowedFormatItem, macros::format_description};
+use serde_json::{Map, Value, json};
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
 
-/// 重置
-const RESET: &str = "\x1b[0m";
-/// 红色字体
-const RED: &str = "\x1b[31m";
-/// 绿色字体
-const GREEN: &str = "\x1b[32m";
-/// 黄色字体
-const YELLOW: &str = "\x1b[33m";
-/// 蓝色字体
-const BLUE: &str = "\x1b[34m";
+use crate::logger::{
+    clock::Clock,
+    event::Event,
+    field::Fields,
+    level::Level,
+    pipeline::Control,
+    request_id::RequestId,
+    theme::{RESET, Theme},
+};
 
-/// 红色背景
-const BG_RED: &str = "\x1b[41m";
-/// 绿色背景
-const BG_GREEN: &str = "\x1b[42m";
-/// 黄色背景
-const BG_YELLOW: &str = "\x1b[43m";
-/// 蓝色背景
-const BG_BLUE: &str = "\x1b[44m";
-/// 紫色背景
-const BG_PURPLE: &str = "\x1b[45m";
-
-const FMT: &[BorrowedFormatItem<'_>] =
-    format_description!("[year repr:last_two]-[month]-[day] [hour]:[minute]:[second].[subsecond digits:4]");
+/// 日志管道中的记录
+pub enum Record {
+    /// 访问日志
+    Access(Message),
+    /// 应用 tracing 事件
+    Event(Event),
+    /// 修改日志线程状态
+    Control(Control),
+}
 
 pub struct Message {
     pub begin: OffsetDateTime,
@@ -35,64 +34,83 @@ pub struct Message {
     pub status: u16,
     pub ip: String,
     pub other: String,
+    pub request_id: Option<RequestId>,
+    pub fields: Fields,
+    /// 输出模板引用但未配置的字段, 只用于模板渲染
+    pub implied: Fields,
+    pub level: Level,
+    /// Logger 的时钟, 决定 `$time` 的格式
+    pub clock: Arc<Clock>,
 }
 
 impl Message {
-    const fn status_color(&self) -> &'static str {
-        match self.status {
-            0..200 => BG_BLUE,
-            200..300 => BG_GREEN,
-            300..400 => BG_YELLOW,
-            400..600 => BG_RED,
-            _ => BG_PURPLE,
+    /// 转为 JSON, 用于结构化输出
+    pub fn to_json(&self) -> Value {
+        let mut fields = Map::new();
+        for (k, v) in &self.fields.0 {
+            fields.insert(k.to_string(), Value::String(v.clone()));
         }
+        json!({
+            "time": self.begin.format(&Rfc3339).ok(),
+            "level": self.level.as_str(),
+            "status": self.status,
+            "elapsed_ms": self.elapsed.as_seconds_f64() * 1000.0,
+            "ip": self.ip,
+            "method": self.method,
+            "path": self.path,
+            "request_id": self.request_id.as_ref().map(|id| &*id.0),
+            "other": self.other.trim(),
+            "fields": fields,
+        })
     }
 
-    const fn elapsed_color(&self) -> &'static str {
-        match (self.elapsed).whole_milliseconds() {
-            0..10 => GREEN,
-            10..20 => BLUE,
-            20..30 => YELLOW,
-            _ => RED,
-        }
+    /// 按名称查找字段值, 包括模板引用的字段
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields.get(name).or_else(|| self.implied.get(name))
     }
 
-    fn method_color(&self) -> &'static str {
-        match self.method.as_str() {
-            "GET" => BG_GREEN,
-            "POST" => BG_BLUE,
-            "PATCH" | "PUT" => BG_YELLOW,
-            "DELETE" => BG_RED,
-            _ => BG_PURPLE,
-        }
-    }
-
-    fn format(&self) -> String {
-        match self.begin.format(FMT) {
-            Ok(s) => s,
-            Err(_) => format!("{}", self.begin),
-        }
+    pub(crate) fn format(&self) -> String {
+        self.clock.display(&self.begin)
     }
 
     pub fn write(&self, out: &mut impl Write) -> io::Result<()> {
         write!(out, "[{}] ", self.format())?;
+        self.write_plain(out)
+    }
+
+    /// 不带时间前缀, 用于自带时间戳的输出
+    pub fn write_plain(&self, out: &mut impl Write) -> io::Result<()> {
         write!(out, "SALVO │ ")?;
         write!(out, "{} │ ", self.status)?;
         write!(out, "{:>3.0} │ ", self.elapsed)?;
         write!(out, "{:<15} │ ", self.ip)?;
+        if let Some(id) = &self.request_id {
+            write!(out, "{id} │ ")?;
+        }
         write!(out, "{:>6} │ ", self.method)?;
         write!(out, "{} ", self.path)?;
-        writeln!(out, "{}", self.other)
+        writeln!(out, "{}{}", self.other, self.fields.to_kv())
     }
 
+    /// 使用默认配色输出
     pub fn write_color(&self, out: &mut impl Write) -> io::Result<()> {
+        self.write_themed(out, Theme::default_ref())
+    }
+
+    pub fn write_themed(&self, out: &mut impl Write, theme: &Theme) -> io::Result<()> {
+        let status = theme.status_color(self.status);
+        let elapsed = theme.elapsed_color(self.elapsed.unsigned_abs());
+        let method = theme.method_color(&self.method);
         write!(out, "[{}] ", self.format())?; // 时间
-        write!(out, "{YELLOW}SALVO{RESET} │ ")?; // LOGO
-        write!(out, "{} {} {RESET} │ ", self.status_color(), self.status)?; // 状态吗
-        write!(out, "{}{:>3.0}{RESET} │ ", self.elapsed_color(), self.elapsed)?; // 耗时
-        write!(out, "{YELLOW}{:<15}{RESET} │ ", self.ip)?; // ip地址
-        write!(out, "{} {:>6} {RESET} ", self.method_color(), self.method)?; // 访问方式
+        write!(out, "{}SALVO{RESET} │ ", theme.logo)?; // LOGO
+        write!(out, "{status} {} {RESET} │ ", self.status)?; // 状态吗
+        write!(out, "{elapsed}{:>3.0}{RESET} │ ", self.elapsed)?; // 耗时
+        write!(out, "{}{:<15}{RESET} │ ", theme.ip, self.ip)?; // ip地址
+        if let Some(id) = &self.request_id {
+            write!(out, "{}{id}{RESET} │ ", theme.request_id)?; // 请求 ID
+        }
+        write!(out, "{method} {:>6} {RESET} ", self.method)?; // 访问方式
         write!(out, "{} ", self.path)?; // 访问路径
-        writeln!(out, "{RED}{}{RESET}", self.other) // 其他信息
+        writeln!(out, "{}{}{RESET}{}", theme.other, self.other, self.fields.to_kv()) // 其他信息
     }
 }
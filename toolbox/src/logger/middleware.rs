@@ -1,35 +1,224 @@
-use std::{fmt::Write, sync::Arc, thread};
+use std::{
+    fmt::Write,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
 
 use crossbeam_channel::Sender;
 use percent_encoding::percent_decode;
 use salvo::{Depot, FlowCtrl, Handler, Request, Response, async_trait, conn::SocketAddr, http::header::LOCATION};
-use time::OffsetDateTime;
 
-use crate::logger::{
-    message::Message,
-    output::{OutFile, Output, OutputMethod, Stdout},
+use crate::{
+    logger::{
+        admin::LoggerConfig,
+        capture::Capture,
+        clock::{Clock, clock},
+        event::LoggerLayer,
+        field::{Field, Fields},
+        filter::Filter,
+        hook::{Alert, Condition, Hook},
+        level::Level,
+        message::{Message, Record},
+        output::{OutFile, OutputMethod, Stdout},
+        pipeline::{Control, Pipeline, Sink},
+        request_id::RequestIdConfig,
+    },
+    real_ip::{RealIp, RealIpResolver},
 };
 
 #[derive(Clone)]
 pub struct Logger {
-    sender: Sender<Message>,
+    sender: Sender<Record>,
+    request_id: Option<RequestIdConfig>,
+    fields: Vec<Field>,
+    /// 输出模板引用的字段
+    template_fields: Arc<[Field]>,
+    real_ip: Option<RealIpResolver>,
+    capture: Option<Capture>,
+    /// 慢请求阈值, 单位毫秒, 可在运行时修改
+    slow: Arc<AtomicU64>,
+    clock: Arc<Clock>,
 }
 
 impl Logger {
-    pub fn new(mut writers: Vec<OutputMethod>) -> Self {
-        let (sender, rx) = crossbeam_channel::unbounded::<Message>();
-
-        thread::spawn(move || {
-            for msg in rx {
-                for writer in writers.iter_mut() {
-                    if let Err(err) = writer.output(&msg) {
-                        eprintln!("输出日志失败: {err}");
-                    }
+    /// 使用默认名称创建输出, 同类输出依次命名为 `file`, `file#2`, 见 [`Sink::from_outputs`]
+    pub fn new(writers: Vec<OutputMethod>) -> Self {
+        let sinks = Sink::from_outputs(writers);
+        let pipeline = Pipeline::new(sinks).expect("默认名称不会重复");
+        Self::start(pipeline)
+    }
+
+    /// 使用指定名称的输出, 名称重复时返回错误
+    pub fn with_sinks(sinks: Vec<Sink>) -> Result<Self, String> {
+        Pipeline::new(sinks).map(Self::start)
+    }
+
+    fn start(pipeline: Pipeline) -> Self {
+        let (sender, rx) = crossbeam_channel::unbounded::<Record>();
+        let mut template_fields: Vec<Field> = Vec::new();
+        for field in pipeline.sinks.iter().flat_map(|s| s.output.template_fields()) {
+            if !template_fields.contains(&field) {
+                template_fields.push(field);
+            }
+        }
+        thread::spawn(move || pipeline.run(rx));
+
+        Self {
+            sender,
+            request_id: Some(RequestIdConfig::default()),
+            fields: Vec::new(),
+            template_fields: template_fields.into(),
+            real_ip: None,
+            capture: None,
+            slow: Arc::new(AtomicU64::new(1000)),
+            clock: Arc::new(clock().clone()),
+        }
+    }
+
+    /// 创建 tracing Layer, 应用日志与访问日志共用同一组输出
+    pub fn layer(&self) -> LoggerLayer {
+        LoggerLayer {
+            sender: self.sender.clone(),
+            level: tracing::Level::INFO,
+            clock: self.clock.clone(),
+        }
+    }
+
+    /// 在日志线程中修改输出与告警
+    pub fn control(&self, control: impl FnOnce(&mut Pipeline) + Send + 'static) {
+        let control: Control = Box::new(control);
+        if let Err(err) = self.sender.send(Record::Control(control)) {
+            eprintln!("Send 日志时出现错误 {err}")
+        }
+    }
+
+    /// 设置时钟, 默认使用全局时钟 [`clock`]
+    pub fn clock(mut self, clock: Clock) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// 慢请求阈值, 超过时日志等级为 Warn, 默认 1 秒
+    pub fn slow(self, slow: Duration) -> Self {
+        self.set_slow(slow);
+        self
+    }
+
+    /// 运行时修改慢请求阈值
+    pub fn set_slow(&self, slow: Duration) {
+        self.slow.store(slow.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    /// 运行时修改输出的最低等级
+    pub fn set_level(&self, name: &str, level: Level) {
+        let name = name.to_string();
+        self.control(move |p| p.sinks_named(&name).for_each(|s| s.level = level));
+    }
+
+    /// 运行时启用或停用输出
+    pub fn set_enabled(&self, name: &str, enabled: bool) {
+        let name = name.to_string();
+        self.control(move |p| p.sinks_named(&name).for_each(|s| s.enabled = enabled));
+    }
+
+    /// 运行时替换输出的过滤规则, 与 [`OutputMethod::filter`] 相同, None 时移除过滤
+    pub fn set_filter(&self, name: &str, filter: Option<Filter>) {
+        let name = name.to_string();
+        self.control(move |p| {
+            if let Some(sink) = p.sinks_named(&name).next() {
+                sink.set_filter(filter);
+            }
+        });
+    }
+
+    /// 在日志线程中修改输出并等待结果
+    fn control_result(
+        &self,
+        control: impl FnOnce(&mut Pipeline) -> Result<(), String> + Send + 'static,
+    ) -> Result<(), String> {
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        self.control(move |p| {
+            tx.send(control(p)).ok();
+        });
+        rx.recv().unwrap_or_else(|_| Err("日志线程已退出".into()))
+    }
+
+    /// 运行时添加输出, 名称已存在时返回错误
+    pub fn add_sink(&self, sink: Sink) -> Result<(), String> {
+        self.control_result(move |p| p.add(sink))
+    }
+
+    /// 运行时替换全部输出, 名称重复时保持不变并返回错误, 新模板引用而创建时未采集的字段输出为 `-`
+    ///
+    /// 只有输出方式时使用 [`Sink::from_outputs`] 生成默认名称
+    pub fn reload(&self, sinks: Vec<Sink>) -> Result<(), String> {
+        self.control_result(move |p| p.replace(sinks))
+    }
+
+    /// 应用运行时配置
+    pub fn apply(&self, config: LoggerConfig) {
+        if let Some(slow) = config.slow_ms {
+            self.set_slow(Duration::from_millis(slow));
+        }
+        self.control(move |p| p.apply(&config.sinks));
+    }
+
+    /// 收到 SIGHUP 时调用 load 重新加载配置, 需在 tokio 运行时中调用
+    #[cfg(unix)]
+    pub fn reload_on_sighup(&self, load: impl Fn() -> Option<LoggerConfig> + Send + 'static) -> std::io::Result<()> {
+        use tokio::signal::unix::{SignalKind, signal};
+
+        let mut hangup = signal(SignalKind::hangup())?;
+        let logger = self.clone();
+        tokio::spawn(async move {
+            while hangup.recv().await.is_some() {
+                if let Some(config) = load() {
+                    logger.apply(config);
                 }
             }
         });
+        Ok(())
+    }
 
-        Self { sender }
+    /// 添加告警回调, 访问日志满足 condition 时在日志线程中调用
+    pub fn hook(self, condition: Condition, hook: impl Hook + Send + 'static) -> Self {
+        let alert = Alert::new(condition, hook);
+        self.control(move |pipeline| pipeline.alerts.push(alert));
+        self
+    }
+
+    /// 设置请求 ID 配置, None 时不生成请求 ID
+    pub fn request_id(mut self, config: Option<RequestIdConfig>) -> Self {
+        self.request_id = config;
+        self
+    }
+
+    /// 追加采集的日志字段
+    pub fn field(mut self, field: Field) -> Self {
+        self.fields.push(field);
+        self
+    }
+
+    /// 设置真实 IP 解析器, 记录受信任代理转发的客户端 IP
+    pub fn real_ip(mut self, resolver: RealIpResolver) -> Self {
+        self.real_ip = Some(resolver);
+        self
+    }
+
+    /// 开启请求/响应体采集
+    pub fn capture(mut self, capture: Capture) -> Self {
+        self.capture = Some(capture);
+        self
+    }
+
+    /// 设置采集的日志字段
+    pub fn fields(mut self, fields: Vec<Field>) -> Self {
+        self.fields = fields;
+        self
     }
 }
 
@@ -45,39 +234,81 @@ impl Default for Logger {
 #[async_trait]
 impl Handler for Logger {
     async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
-        let begin = OffsetDateTime::now_local().unwrap();
+        let begin = self.clock.now();
+        let start = Instant::now();
         let method = req.method().to_string();
-        let ip = match req.remote_addr() {
-            SocketAddr::IPv4(ip) => ip.ip().to_string(),
-            SocketAddr::IPv6(ip) => ip.ip().to_string(),
-            SocketAddr::Unix(ip) => match ip.as_pathname() {
-                Some(path) => path.display().to_string(),
-                None => "Unknow".to_string(),
-            },
-            _ => "Unknow".to_string(),
+        let ip = if let Some(ip) = req.extensions().get::<RealIp>() {
+            ip.to_string()
+        } else if let Some(ip) = self.real_ip.as_ref().and_then(|r| r.resolve(req)) {
+            req.extensions_mut().insert(RealIp(ip));
+            ip.to_string()
+        } else {
+            match req.remote_addr() {
+                SocketAddr::IPv4(ip) => ip.ip().to_string(),
+                SocketAddr::IPv6(ip) => ip.ip().to_string(),
+                SocketAddr::Unix(ip) => match ip.as_pathname() {
+                    Some(path) => path.display().to_string(),
+                    None => "Unknow".to_string(),
+                },
+                _ => "Unknow".to_string(),
+            }
         };
         let mut path = percent_decode(req.uri().path().as_bytes())
             .decode_utf8_lossy()
             .to_string();
+        let capture = self.capture.as_ref().filter(|c| c.matches(req.uri().path()));
+        let mut fields = Fields::default();
+        if let Some(capture) = capture {
+            capture.request(req, &mut fields).await;
+        }
+        let request_id = self.request_id.as_ref().map(|config| {
+            let id = config.resolve(req);
+            config.attach(&id, req, depot);
+            id
+        });
 
         ctrl.call_next(req, depot, res).await;
 
+        if let (Some(config), Some(id)) = (&self.request_id, &request_id) {
+            config.echo(id, res);
+        }
+
         let mut other = String::new();
         if let Ok(v) = depot.get::<Arc<str>>("error") {
             write!(&mut other, " Error({v})").ok();
         }
-        
+
         if let Ok(v) = depot.get::<Arc<str>>("other") {
             write!(&mut other, " {v}").ok();
         }
 
+        for field in &self.fields {
+            if let Some(value) = field.collect(req, depot, res) {
+                fields.push(field.name(), value);
+            }
+        }
+        // 模板引用的字段单独存放, 不出现在其他输出中
+        let mut implied = Fields::default();
+        for field in self.template_fields.iter().filter(|f| !self.fields.contains(f)) {
+            if let Some(value) = field.collect(req, depot, res) {
+                implied.push(field.name(), value);
+            }
+        }
+        if let Some(capture) = capture {
+            capture.response(res, &mut fields);
+        }
+
         let status = res.status_code.unwrap_or_default().as_u16();
         // 是否重定向
         if let Some(p) = res.headers().get(LOCATION) {
             path.push_str(" -> ");
             path.push_str(&percent_decode(p.as_bytes()).decode_utf8_lossy());
         }
-        let elapsed = OffsetDateTime::now_local().unwrap() - begin;
+        let elapsed = start.elapsed();
+
+        let slow = Duration::from_millis(self.slow.load(Ordering::Relaxed));
+        let level = Level::of(status, elapsed, slow);
+        let elapsed = time::Duration::try_from(elapsed).unwrap_or(time::Duration::MAX);
 
         let msg = Message {
             begin,
@@ -87,10 +318,40 @@ impl Handler for Logger {
             method,
             path,
             other,
+            request_id,
+            fields,
+            implied,
+            level,
+            clock: self.clock.clone(),
         };
 
-        if let Err(err) = self.sender.send(msg) {
+        if let Err(err) = self.sender.send(Record::Access(msg)) {
             eprintln!("Send 日志时出现错误 {err}")
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sink_names() {
+        // 同类输出使用不同的默认名称
+        let logger = Logger::new(vec![
+            OutputMethod::Stdout(Stdout::default()),
+            OutputMethod::Stdout(Stdout::default()),
+        ]);
+        let sink = || Sink::new("stdout#2", OutputMethod::Stdout(Stdout::default()));
+        assert!(logger.add_sink(sink()).is_err());
+        assert!(
+            logger
+                .add_sink(Sink::new("audit", OutputMethod::Stdout(Stdout::default())))
+                .is_ok()
+        );
+        assert!(logger.reload(vec![sink(), sink()]).is_err());
+        assert!(logger.reload(vec![sink()]).is_ok());
+
+        assert!(Logger::with_sinks(vec![sink(), sink()]).is_err());
+    }
+}
@@ -1,5 +1,33 @@
+pub mod admin;
+pub mod capture;
+pub mod clock;
+pub mod event;
+pub mod field;
+pub mod filter;
+pub mod hook;
+#[cfg(unix)]
+pub mod journald;
+pub mod level;
 pub mod message;
 pub mod middleware;
 pub mod output;
+pub mod pipeline;
+pub mod request_id;
+pub mod shipper;
+pub mod syslog;
+pub mod template;
+pub mod theme;
 
+pub use admin::{LoggerAdmin, LoggerConfig};
+pub use capture::Capture;
+pub use clock::{Clock, TimeZone};
+pub use event::LoggerLayer;
+pub use field::Field;
+pub use filter::{Filter, FilterConfig};
+pub use hook::{Condition, Hook};
+pub use level::Level;
 pub use middleware::*;
+pub use pipeline::{Sink, SinkConfig};
+pub use request_id::{RequestId, RequestIdConfig};
+pub use template::Template;
+pub use theme::Theme;
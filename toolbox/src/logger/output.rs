@@ -1,17 +1,45 @@
 use std::{
+    env,
     fs::{self, File},
-    io,
+    io::{self, IsTerminal, Write},
     path::PathBuf,
 };
 
 use enum_dispatch::enum_dispatch;
 use time::OffsetDateTime;
 
-use crate::logger::message::Message;
+use crate::{
+    audit::AuditEntry,
+    logger::{
+        clock::clock,
+        event::{ACCESS_TARGET, AUDIT_TARGET, Event},
+        field::Field,
+        filter::Filter,
+        level::Level,
+        message::Message,
+        shipper::Shipper,
+        syslog::Syslog,
+        template::Template,
+        theme::Theme,
+    },
+};
+
+#[cfg(unix)]
+use crate::logger::journald::Journald;
 
 #[enum_dispatch(OutputMethod)]
 pub trait Output {
     fn output(&mut self, message: &Message) -> io::Result<()>;
+
+    /// 输出应用 tracing 事件, 默认忽略
+    fn event(&mut self, _event: &Event) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// 输出审计日志, 默认返回错误, 用于审计日志的自定义输出需实现该方法
+    fn audit(&mut self, _entry: &AuditEntry) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "该输出不支持审计日志"))
+    }
 }
 
 /// 输出方式
@@ -19,30 +47,202 @@ pub trait Output {
 pub enum OutputMethod {
     Stdout(Stdout),
     OutputFile(OutFile),
+    Tracing(Tracing),
+    Filtered(Filtered),
+    Syslog(Syslog),
+    Shipper(Shipper),
+    #[cfg(unix)]
+    Journald(Journald),
+    /// 自定义输出, 动态分发
+    Custom(Box<dyn Output + Send>),
+}
+
+impl OutputMethod {
+    /// 默认名称, 用于运行时按名称调整输出
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Stdout(stdout) if stdout.target == Target::Stderr => "stderr",
+            Self::Stdout(_) => "stdout",
+            Self::OutputFile(_) => "file",
+            Self::Tracing(_) => "tracing",
+            Self::Filtered(filtered) => filtered.output.name(),
+            Self::Syslog(_) => "syslog",
+            Self::Shipper(_) => "shipper",
+            #[cfg(unix)]
+            Self::Journald(_) => "journald",
+            Self::Custom(_) => "custom",
+        }
+    }
+
+    /// 模板中引用的字段, 创建 Logger 时自动采集
+    pub fn template_fields(&self) -> Vec<Field> {
+        let template = match self {
+            Self::Stdout(stdout) => stdout.template.as_ref(),
+            Self::OutputFile(file) => file.template.as_ref(),
+            Self::Filtered(filtered) => return filtered.output.template_fields(),
+            _ => None,
+        };
+        template.map(|t| t.fields().collect()).unwrap_or_default()
+    }
+
+    /// 包装自定义输出
+    pub fn custom(output: impl Output + Send + 'static) -> Self {
+        Self::Custom(Box::new(output))
+    }
+
+    /// 为该输出附加过滤规则
+    pub fn filter(self, filter: Filter) -> Self {
+        Self::Filtered(Filtered {
+            filter,
+            output: Box::new(self),
+        })
+    }
+}
+
+impl<T: Output + ?Sized> Output for Box<T> {
+    fn output(&mut self, message: &Message) -> io::Result<()> {
+        (**self).output(message)
+    }
+
+    fn event(&mut self, event: &Event) -> io::Result<()> {
+        (**self).event(event)
+    }
+
+    fn audit(&mut self, entry: &AuditEntry) -> io::Result<()> {
+        (**self).audit(entry)
+    }
+}
+
+/// 终端输出目标
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Stdout,
+    Stderr,
+}
+
+/// 彩色输出模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    /// 按 NO_COLOR / FORCE_COLOR 环境变量与是否为终端自动判断
+    Auto,
+    Always,
+    Never,
 }
 
 /// 标准输出
 #[derive(Debug)]
 pub struct Stdout {
+    /// 是否彩色输出, 由 [`Stdout::color`] 按 [`ColorMode`] 确定
     pub color: bool,
-    pub output: std::io::Stdout,
+    pub output: io::Stdout,
+    pub target: Target,
+    pub theme: Box<Theme>,
+    /// 自定义格式模板, 设置后忽略 color
+    pub template: Option<Template>,
+    mode: ColorMode,
+}
+
+impl Stdout {
+    /// 输出到标准错误
+    pub fn stderr() -> Self {
+        Self::default().target(Target::Stderr)
+    }
+
+    pub fn target(mut self, target: Target) -> Self {
+        self.target = target;
+        self.color = self.mode.resolve(target);
+        self
+    }
+
+    pub fn color(mut self, mode: impl Into<ColorMode>) -> Self {
+        self.mode = mode.into();
+        self.color = self.mode.resolve(self.target);
+        self
+    }
+
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = Box::new(theme);
+        self
+    }
+
+    pub fn template(mut self, template: Template) -> Self {
+        self.template = Some(template);
+        self
+    }
+
+    fn write(&mut self, write: impl FnOnce(&Self, &mut dyn Write) -> io::Result<()>) -> io::Result<()> {
+        match self.target {
+            Target::Stdout => write(self, &mut self.output.lock()),
+            Target::Stderr => write(self, &mut io::stderr().lock()),
+        }
+    }
+}
+
+impl ColorMode {
+    /// 确定是否彩色输出, Auto 时 NO_COLOR 非空则关闭, FORCE_COLOR 非 0 则开启, 否则仅在终端中开启
+    fn resolve(self, target: Target) -> bool {
+        match self {
+            Self::Always => return true,
+            Self::Never => return false,
+            Self::Auto => {}
+        }
+        if env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+            return false;
+        }
+        if let Some(force) = env::var_os("FORCE_COLOR") {
+            return force != "0";
+        }
+        match target {
+            Target::Stdout => io::stdout().is_terminal(),
+            Target::Stderr => io::stderr().is_terminal(),
+        }
+    }
+}
+
+impl From<bool> for ColorMode {
+    fn from(color: bool) -> Self {
+        if color { Self::Always } else { Self::Never }
+    }
 }
 
 impl Output for Stdout {
     fn output(&mut self, message: &Message) -> io::Result<()> {
-        if self.color {
-            message.write_color(&mut self.output)
-        } else {
-            message.write(&mut self.output)
-        }
+        self.write(|this, mut out| {
+            if let Some(template) = &this.template {
+                template.render(message, &mut out)
+            } else if this.color {
+                message.write_themed(&mut out, &this.theme)
+            } else {
+                message.write(&mut out)
+            }
+        })
+    }
+
+    fn event(&mut self, event: &Event) -> io::Result<()> {
+        self.write(|this, mut out| {
+            if this.color {
+                event.write_themed(&mut out, &this.theme)
+            } else {
+                event.write(&mut out)
+            }
+        })
+    }
+
+    fn audit(&mut self, entry: &AuditEntry) -> io::Result<()> {
+        self.write(|_, out| writeln!(out, "{}", entry.line()))
     }
 }
 
 impl Default for Stdout {
     fn default() -> Self {
+        let mode = ColorMode::Auto;
         Self {
-            color: true,
-            output: std::io::stdout(),
+            color: mode.resolve(Target::Stdout),
+            output: io::stdout(),
+            target: Target::Stdout,
+            theme: Box::default(),
+            template: None,
+            mode,
         }
     }
 }
@@ -55,11 +255,13 @@ pub struct OutFile {
     pub path: PathBuf,
     pub delete: Option<i64>,
     pub created_at: OffsetDateTime,
+    /// 自定义格式模板
+    pub template: Option<Template>,
 }
 
 impl OutFile {
     pub fn new(path: PathBuf, name: String, delete: Option<i64>) -> io::Result<Self> {
-        let created_at = OffsetDateTime::now_local().unwrap();
+        let created_at = clock().now();
         fs::create_dir_all(&path)?;
         let file_name = name.replace("{date}", &created_at.date().to_string());
         let file = File::options().create(true).append(true).open(path.join(&file_name))?;
@@ -69,6 +271,7 @@ impl OutFile {
             delete,
             created_at,
             file,
+            template: None,
         })
     }
     pub fn delete_log_file(&self, now: &OffsetDateTime) -> io::Result<()> {
@@ -100,11 +303,110 @@ impl Default for OutFile {
     }
 }
 
+impl OutFile {
+    /// 跨天时切换日志文件
+    fn rotate(&mut self, now: &OffsetDateTime) -> io::Result<()> {
+        if now.date() != self.created_at.date() {
+            self.file = self.update_log_file(now)?;
+            self.created_at = *now;
+        }
+        Ok(())
+    }
+}
+
 impl Output for OutFile {
     fn output(&mut self, message: &Message) -> io::Result<()> {
-        if message.begin.date() != self.created_at.date() {
-            self.file = self.update_log_file(&message.begin)?;
+        self.rotate(&message.begin)?;
+        match &self.template {
+            Some(template) => template.render(message, &mut self.file),
+            None => message.write(&mut self.file),
         }
-        message.write(&mut self.file)
+    }
+
+    fn event(&mut self, event: &Event) -> io::Result<()> {
+        self.rotate(&event.time)?;
+        event.write(&mut self.file)
+    }
+
+    fn audit(&mut self, entry: &AuditEntry) -> io::Result<()> {
+        self.rotate(&entry.time)?;
+        writeln!(self.file, "{}", entry.line())
+    }
+}
+
+/// 以 tracing 事件输出访问日志, 交由应用的 tracing subscriber 处理
+///
+/// 事件 target 为 [`ACCESS_TARGET`], 事件等级取自 [`Message::level`]
+#[derive(Debug, Default)]
+pub struct Tracing;
+
+impl Output for Tracing {
+    fn output(&mut self, message: &Message) -> io::Result<()> {
+        let elapsed_ms = message.elapsed.as_seconds_f64() * 1000.0;
+        let request_id = message.request_id.as_ref().map(|id| &*id.0).unwrap_or_default();
+        macro_rules! access_event {
+            ($level:expr) => {
+                tracing::event!(
+                    target: ACCESS_TARGET,
+                    $level,
+                    status = message.status,
+                    elapsed_ms,
+                    ip = %message.ip,
+                    method = %message.method,
+                    path = %message.path,
+                    request_id,
+                    other = message.other.trim(),
+                    "{} {} {}",
+                    message.method,
+                    message.path,
+                    message.status,
+                )
+            };
+        }
+        match message.level {
+            Level::Error => access_event!(tracing::Level::ERROR),
+            Level::Warn => access_event!(tracing::Level::WARN),
+            Level::Info => access_event!(tracing::Level::INFO),
+        }
+        Ok(())
+    }
+
+    fn audit(&mut self, entry: &AuditEntry) -> io::Result<()> {
+        let event = &entry.event;
+        tracing::info!(
+            target: AUDIT_TARGET,
+            seq = entry.seq,
+            kind = event.kind.as_str(),
+            outcome = ?event.outcome,
+            actor = event.actor.as_deref().unwrap_or_default(),
+            hash = %entry.hash,
+            "{}",
+            entry.line(),
+        );
+        Ok(())
+    }
+}
+
+/// 带过滤规则的输出, 仅过滤访问日志, 应用事件直接输出
+pub struct Filtered {
+    pub filter: Filter,
+    pub output: Box<OutputMethod>,
+}
+
+impl Output for Filtered {
+    fn output(&mut self, message: &Message) -> io::Result<()> {
+        if self.filter.allow(message) {
+            self.output.output(message)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn event(&mut self, event: &Event) -> io::Result<()> {
+        self.output.event(event)
+    }
+
+    fn audit(&mut self, entry: &AuditEntry) -> io::Result<()> {
+        self.output.audit(entry)
     }
 }
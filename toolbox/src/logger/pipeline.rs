@@ -0,0 +1,261 @@
+use std::sync::Arc;
+
+use crossbeam_channel::Receiver;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::logger::{
+    event::Event,
+    filter::{Filter, FilterConfig},
+    hook::Alert,
+    level::Level,
+    message::{Message, Record},
+    output::{Output, OutputMethod, Tracing},
+};
+
+/// 在日志线程中修改 Pipeline 的指令
+pub type Control = Box<dyn FnOnce(&mut Pipeline) + Send>;
+
+/// 带名称的输出, 名称用于运行时调整, 同一 Logger 中不能重复
+pub struct Sink {
+    pub name: Arc<str>,
+    pub enabled: bool,
+    /// 最低输出等级
+    pub level: Level,
+    pub output: OutputMethod,
+}
+
+impl Sink {
+    pub fn new(name: impl Into<Arc<str>>, output: OutputMethod) -> Self {
+        Self {
+            name: name.into(),
+            enabled: true,
+            level: Level::Info,
+            output,
+        }
+    }
+
+    /// 使用默认名称, 同类输出依次命名为 `file`, `file#2`, `file#3`
+    pub fn from_outputs(outputs: Vec<OutputMethod>) -> Vec<Self> {
+        let mut sinks: Vec<Self> = Vec::with_capacity(outputs.len());
+        for output in outputs {
+            let name = output.name();
+            let count = sinks.iter().filter(|s| s.output.name() == name).count();
+            let sink = match count {
+                0 => Self::new(name, output),
+                n => Self::new(format!("{name}#{}", n + 1), output),
+            };
+            sinks.push(sink);
+        }
+        sinks
+    }
+
+    /// 替换过滤规则, 使用 [`Filtered`] 包装输出, None 时移除过滤
+    pub fn set_filter(&mut self, filter: Option<Filter>) {
+        let output = std::mem::replace(&mut self.output, OutputMethod::Tracing(Tracing));
+        let output = match output {
+            OutputMethod::Filtered(filtered) => *filtered.output,
+            output => output,
+        };
+        self.output = match filter {
+            Some(filter) => output.filter(filter),
+            None => output,
+        };
+    }
+
+    fn output(&mut self, message: &Message) -> std::io::Result<()> {
+        if !self.enabled || message.level < self.level {
+            return Ok(());
+        }
+        self.output.output(message)
+    }
+
+    fn event(&mut self, event: &Event) -> std::io::Result<()> {
+        if !self.enabled || Level::from(event.level) < self.level {
+            return Ok(());
+        }
+        self.output.event(event)
+    }
+}
+
+impl From<OutputMethod> for Sink {
+    fn from(output: OutputMethod) -> Self {
+        Self::new(output.name(), output)
+    }
+}
+
+/// 可序列化的输出配置, 用于管理接口与配置重载, 未设置的字段保持不变
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SinkConfig {
+    pub name: String,
+    pub level: Option<Level>,
+    pub enabled: Option<bool>,
+    /// 未设置时保持不变, 为 null 时移除过滤规则
+    #[serde(default, deserialize_with = "present", skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Option<FilterConfig>>,
+}
+
+/// 区分字段缺失与 null
+fn present<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Option<FilterConfig>>, D::Error> {
+    Option::deserialize(deserializer).map(Some)
+}
+
+/// 日志线程持有的输出与告警
+pub struct Pipeline {
+    pub sinks: Vec<Sink>,
+    pub alerts: Vec<Alert>,
+}
+
+impl Pipeline {
+    /// 输出名称重复时返回错误
+    pub fn new(sinks: Vec<Sink>) -> Result<Self, String> {
+        check_names(&sinks)?;
+        Ok(Self {
+            sinks,
+            alerts: Vec::new(),
+        })
+    }
+
+    /// 添加输出, 名称已存在时返回错误
+    pub fn add(&mut self, sink: Sink) -> Result<(), String> {
+        if self.sinks.iter().any(|s| s.name == sink.name) {
+            return Err(format!("日志输出名称重复: {}", sink.name));
+        }
+        self.sinks.push(sink);
+        Ok(())
+    }
+
+    /// 替换全部输出, 名称重复时保持不变并返回错误
+    pub fn replace(&mut self, sinks: Vec<Sink>) -> Result<(), String> {
+        check_names(&sinks)?;
+        self.sinks = sinks;
+        Ok(())
+    }
+
+    /// 名称匹配的所有输出
+    pub fn sinks_named<'a>(&'a mut self, name: &'a str) -> impl Iterator<Item = &'a mut Sink> + 'a {
+        self.sinks.iter_mut().filter(move |s| &*s.name == name)
+    }
+
+    /// 应用配置
+    pub fn apply(&mut self, configs: &[SinkConfig]) {
+        for config in configs {
+            for sink in self.sinks_named(&config.name) {
+                if let Some(level) = config.level {
+                    sink.level = level;
+                }
+                if let Some(enabled) = config.enabled {
+                    sink.enabled = enabled;
+                }
+                if let Some(filter) = &config.filter {
+                    sink.set_filter(filter.as_ref().map(Filter::from));
+                }
+            }
+        }
+    }
+
+    pub(crate) fn run(mut self, rx: Receiver<Record>) {
+        for record in rx {
+            self.dispatch(record);
+        }
+    }
+
+    fn dispatch(&mut self, record: Record) {
+        match record {
+            Record::Access(msg) => {
+                for sink in self.sinks.iter_mut() {
+                    if let Err(err) = sink.output(&msg) {
+                        eprintln!("输出日志失败: {err}");
+                    }
+                }
+                for alert in self.alerts.iter_mut() {
+                    alert.check(&msg);
+                }
+            }
+            Record::Event(event) => {
+                for sink in self.sinks.iter_mut() {
+                    if let Err(err) = sink.event(&event) {
+                        eprintln!("输出日志失败: {err}");
+                    }
+                }
+            }
+            Record::Control(control) => control(self),
+        }
+    }
+}
+
+fn check_names(sinks: &[Sink]) -> Result<(), String> {
+    for (i, sink) in sinks.iter().enumerate() {
+        if sinks[..i].iter().any(|s| s.name == sink.name) {
+            return Err(format!(
+                "日志输出名称重复: {}, 请使用 Sink::new 指定不同的名称",
+                sink.name
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logger::output::Stdout;
+
+    fn stdout(name: &str) -> Sink {
+        Sink::new(name, OutputMethod::Stdout(Stdout::default()))
+    }
+
+    #[test]
+    fn names_must_be_unique() {
+        assert!(Pipeline::new(vec![stdout("a"), stdout("a")]).is_err());
+        let mut pipeline = Pipeline::new(vec![stdout("a"), stdout("b")]).unwrap();
+        assert!(pipeline.add(stdout("b")).is_err());
+        assert!(pipeline.replace(vec![stdout("c"), stdout("c")]).is_err());
+        assert_eq!(pipeline.sinks.len(), 2);
+    }
+
+    #[test]
+    fn default_names() {
+        let sinks = Sink::from_outputs(vec![
+            OutputMethod::Stdout(Stdout::default()),
+            OutputMethod::Tracing(Tracing),
+            OutputMethod::Stdout(Stdout::default()),
+            OutputMethod::Stdout(Stdout::default()),
+        ]);
+        let names: Vec<_> = sinks.iter().map(|s| &*s.name).collect();
+        assert_eq!(names, ["stdout", "tracing", "stdout#2", "stdout#3"]);
+
+        let sinks = Sink::from_outputs(vec![
+            OutputMethod::Stdout(Stdout::default()),
+            OutputMethod::Stdout(Stdout::stderr()),
+        ]);
+        let names: Vec<_> = sinks.iter().map(|s| &*s.name).collect();
+        assert_eq!(names, ["stdout", "stderr"]);
+        assert!(Pipeline::new(sinks).is_ok());
+    }
+
+    #[test]
+    fn apply_filter_config() {
+        let mut pipeline = Pipeline::new(vec![stdout("a")]).unwrap();
+        let filtered = |p: &Pipeline| matches!(p.sinks[0].output, OutputMethod::Filtered(_));
+
+        // 缺失时保持不变, 对象时设置, null 时移除
+        let config: Vec<SinkConfig> = serde_json::from_str(r#"[{"name":"a","level":"warn"}]"#).unwrap();
+        pipeline.apply(&config);
+        assert!(!filtered(&pipeline));
+        assert_eq!(pipeline.sinks[0].level, Level::Warn);
+
+        let config: Vec<SinkConfig> =
+            serde_json::from_str(r#"[{"name":"a","filter":{"skip":["/health"],"status":[[500,599]]}}]"#).unwrap();
+        pipeline.apply(&config);
+        assert!(filtered(&pipeline));
+        pipeline.apply(&config);
+        let OutputMethod::Filtered(inner) = &pipeline.sinks[0].output else {
+            unreachable!()
+        };
+        assert!(matches!(*inner.output, OutputMethod::Stdout(_)));
+
+        let config: Vec<SinkConfig> = serde_json::from_str(r#"[{"name":"a","filter":null}]"#).unwrap();
+        pipeline.apply(&config);
+        assert!(!filtered(&pipeline));
+    }
+}
@@ -0,0 +1,100 @@
+use std::{fmt, sync::Arc};
+
+use derive_more::Deref;
+use salvo::{
+    Depot, Extractible, Request, Writer,
+    http::{HeaderValue, header::HeaderName},
+};
+use ulid::Ulid;
+
+use crate::{global::METADATE, res, resp::Res};
+
+/// 请求 ID
+#[derive(Debug, Clone, Deref, PartialEq, Eq)]
+pub struct RequestId(pub Arc<str>);
+
+impl RequestId {
+    /// 从请求扩展或 Depot 中读取当前请求 ID
+    pub fn current(req: &Request, depot: &Depot) -> Option<Self> {
+        req.extensions()
+            .get::<Self>()
+            .or_else(|| depot.obtain::<Self>().ok())
+            .cloned()
+    }
+}
+
+impl fmt::Display for RequestId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl<'ex> Extractible<'ex> for RequestId {
+    fn metadata() -> &'ex salvo::extract::Metadata {
+        &METADATE
+    }
+
+    async fn extract(req: &'ex mut Request) -> Result<Self, impl Writer + Send + fmt::Debug + 'static> {
+        req.extensions()
+            .get::<Self>()
+            .cloned()
+            .ok_or_else(|| -> Res { res!(500, "请求 ID 不存在: 未启用 Logger 请求 ID") })
+    }
+}
+
+/// 请求 ID 配置
+#[derive(Debug, Clone)]
+pub struct RequestIdConfig {
+    /// 读取与回写的请求头
+    pub header: HeaderName,
+    /// 是否沿用客户端携带的请求 ID
+    pub trust_incoming: bool,
+    /// ID 生成函数
+    pub generator: fn() -> String,
+}
+
+impl RequestIdConfig {
+    /// 读取请求头中的 ID, 不存在或不合法时生成新的 ID
+    pub fn resolve(&self, req: &Request) -> RequestId {
+        if self.trust_incoming
+            && let Some(id) = req.headers().get(&self.header).and_then(|v| v.to_str().ok())
+            && is_valid_id(id)
+        {
+            return RequestId(id.into());
+        }
+        RequestId((self.generator)().into())
+    }
+
+    /// 写入请求扩展与 Depot
+    pub fn attach(&self, id: &RequestId, req: &mut Request, depot: &mut Depot) {
+        req.extensions_mut().insert(id.clone());
+        depot.inject(id.clone());
+    }
+
+    /// 回写到响应头
+    pub fn echo(&self, id: &RequestId, res: &mut salvo::Response) {
+        if let Ok(value) = HeaderValue::from_str(id) {
+            res.headers_mut().insert(self.header.clone(), value);
+        }
+    }
+}
+
+impl Default for RequestIdConfig {
+    fn default() -> Self {
+        Self {
+            header: HeaderName::from_static("x-request-id"),
+            trust_incoming: true,
+            generator: ulid,
+        }
+    }
+}
+
+/// 生成 ULID
+pub fn ulid() -> String {
+    Ulid::new().to_string()
+}
+
+/// 客户端 ID 只接受 128 字节以内的可见 ASCII 字符
+fn is_valid_id(id: &str) -> bool {
+    !id.is_empty() && id.len() <= 128 && id.bytes().all(|b| b.is_ascii_graphic())
+}
@@ -0,0 +1,509 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufRead, BufReader, Seek, SeekFrom, Write},
+    net::{SocketAddr, TcpStream, ToSocketAddrs},
+    path::PathBuf,
+    sync::{Arc, Mutex, MutexGuard},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use crossbeam_channel::{Receiver, RecvTimeoutError, Sender, TrySendError};
+
+use crate::{
+    audit::AuditEntry,
+    logger::{event::Event, message::Message, output::Output},
+};
+
+/// 收集端地址
+#[derive(Debug, Clone)]
+pub enum Endpoint {
+    /// TCP 长连接, 每行一条 JSON
+    Tcp(SocketAddr),
+    /// HTTP POST, body 为 NDJSON, 仅支持 http
+    Http {
+        addr: SocketAddr,
+        host: String,
+        path: String,
+    },
+}
+
+impl Endpoint {
+    /// 解析 `tcp://host:port` 或 `http://host:port/path`
+    pub fn parse(url: &str) -> io::Result<Self> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidInput, format!("无效的日志收集地址: {url}"));
+        let resolve = |host: &str, port: u16| (host, port).to_socket_addrs()?.next().ok_or_else(invalid);
+        if let Some(rest) = url.strip_prefix("tcp://") {
+            let (host, port) = rest.rsplit_once(':').ok_or_else(invalid)?;
+            let port = port.parse().map_err(|_| invalid())?;
+            return Ok(Self::Tcp(resolve(host, port)?));
+        }
+        if let Some(rest) = url.strip_prefix("http://") {
+            let (authority, path) = match rest.find('/') {
+                Some(i) => rest.split_at(i),
+                None => (rest, "/"),
+            };
+            let (host, port) = match authority.rsplit_once(':') {
+                Some((host, port)) => (host, port.parse().map_err(|_| invalid())?),
+                None => (authority, 80),
+            };
+            return Ok(Self::Http {
+                addr: resolve(host, port)?,
+                host: authority.into(),
+                path: path.into(),
+            });
+        }
+        Err(invalid())
+    }
+}
+
+/// 批量推送日志到收集端
+///
+/// 日志在独立的发送线程中按批发送, 达到 batch_size 或距上次发送超过 interval 时发送,
+/// 失败按指数退避重试, 重试仍失败时写入本地 spill 文件, 收集端恢复后先补发 spill 再发送新日志;
+/// 发送队列已满时日志直接写入 spill, 不阻塞日志线程
+pub struct Shipper {
+    pub endpoint: Endpoint,
+    pub batch_size: usize,
+    pub interval: Duration,
+    pub retries: u32,
+    pub backoff: Duration,
+    pub timeout: Duration,
+    /// 发送队列长度
+    pub queue: usize,
+    /// 本地缓冲文件, None 时丢弃发送失败的日志
+    pub spill: Option<PathBuf>,
+    /// 本地缓冲文件最大字节数
+    pub spill_limit: u64,
+    /// 首次输出时启动发送线程
+    worker: Option<(Sender<String>, JoinHandle<()>)>,
+    shared_spill: Option<Arc<Mutex<Spill>>>,
+}
+
+impl Shipper {
+    pub fn new(endpoint: Endpoint) -> Self {
+        Self {
+            endpoint,
+            batch_size: 100,
+            interval: Duration::from_secs(5),
+            retries: 3,
+            backoff: Duration::from_millis(200),
+            timeout: Duration::from_secs(3),
+            queue: 10_000,
+            spill: Some("logs/spill.ndjson".into()),
+            spill_limit: 64 * 1024 * 1024,
+            worker: None,
+            shared_spill: None,
+        }
+    }
+
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// 重试次数与首次退避时间, 之后每次翻倍
+    pub fn retry(mut self, retries: u32, backoff: Duration) -> Self {
+        self.retries = retries;
+        self.backoff = backoff;
+        self
+    }
+
+    /// 发送队列长度, 默认 10000
+    pub fn queue(mut self, queue: usize) -> Self {
+        self.queue = queue.max(1);
+        self
+    }
+
+    pub fn spill(mut self, spill: Option<PathBuf>) -> Self {
+        self.spill = spill;
+        self
+    }
+
+    fn push(&mut self, line: String) -> io::Result<()> {
+        if self.worker.is_none() {
+            let (sender, rx) = crossbeam_channel::bounded(self.queue);
+            let spill = self
+                .spill
+                .clone()
+                .map(|path| Arc::new(Mutex::new(Spill::new(path, self.spill_limit))));
+            let worker = Worker::new(self, spill.clone());
+            let handle = thread::Builder::new()
+                .name("log-shipper".into())
+                .spawn(move || worker.run(rx))?;
+            self.worker = Some((sender, handle));
+            self.shared_spill = spill;
+        }
+        let Some((sender, _)) = &self.worker else {
+            return Ok(());
+        };
+        match sender.try_send(line) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(line) | TrySendError::Disconnected(line)) => match &self.shared_spill {
+                Some(spill) => lock(spill).append(&[line]),
+                None => Err(io::Error::other("日志发送队列已满, 丢弃日志")),
+            },
+        }
+    }
+}
+
+impl Output for Shipper {
+    fn output(&mut self, message: &Message) -> io::Result<()> {
+        self.push(message.to_json().to_string())
+    }
+
+    fn event(&mut self, event: &Event) -> io::Result<()> {
+        self.push(event.to_json().to_string())
+    }
+
+    fn audit(&mut self, entry: &AuditEntry) -> io::Result<()> {
+        self.push(entry.line().to_string())
+    }
+}
+
+impl Drop for Shipper {
+    /// 关闭队列, 等待发送线程发送剩余日志
+    fn drop(&mut self) {
+        if let Some((sender, handle)) = self.worker.take() {
+            drop(sender);
+            handle.join().ok();
+        }
+    }
+}
+
+fn lock(spill: &Mutex<Spill>) -> MutexGuard<'_, Spill> {
+    spill.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// 本地缓冲文件, 日志线程与发送线程共享
+///
+/// 补发时从 offset 处按批读取, 全部补发后删除文件
+struct Spill {
+    path: PathBuf,
+    limit: u64,
+    offset: u64,
+}
+
+impl Spill {
+    fn new(path: PathBuf, limit: u64) -> Self {
+        Self { path, limit, offset: 0 }
+    }
+
+    fn append(&mut self, lines: &[String]) -> io::Result<()> {
+        if fs::metadata(&self.path).map(|m| m.len()).unwrap_or_default() >= self.limit {
+            return Err(io::Error::other("日志缓冲文件已满, 丢弃日志"));
+        }
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut buf = Vec::new();
+        for line in lines {
+            buf.extend_from_slice(line.as_bytes());
+            buf.push(b'\n');
+        }
+        File::options()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(&buf)
+    }
+
+    /// 从 offset 处读取至多 max 行, 返回读取的行与新的 offset
+    fn read(&self, max: usize) -> io::Result<(Vec<String>, u64)> {
+        let mut file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok((Vec::new(), 0)),
+            Err(err) => return Err(err),
+        };
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut reader = BufReader::new(file);
+        let (mut lines, mut offset) = (Vec::new(), self.offset);
+        let mut line = String::new();
+        while lines.len() < max {
+            line.clear();
+            let n = reader.read_line(&mut line)?;
+            // 未写完的行留到下次读取
+            if n == 0 || !line.ends_with('\n') {
+                break;
+            }
+            offset += n as u64;
+            lines.push(line.trim_end().to_string());
+        }
+        Ok((lines, offset))
+    }
+
+    /// 记录补发进度, 已全部补发时删除文件
+    fn commit(&mut self, offset: u64) -> io::Result<()> {
+        self.offset = offset;
+        if fs::metadata(&self.path)?.len() <= offset {
+            fs::remove_file(&self.path)?;
+            self.offset = 0;
+        }
+        Ok(())
+    }
+}
+
+/// 发送线程持有的状态
+struct Worker {
+    endpoint: Endpoint,
+    batch_size: usize,
+    interval: Duration,
+    retries: u32,
+    backoff: Duration,
+    timeout: Duration,
+    spill: Option<Arc<Mutex<Spill>>>,
+    buffer: Vec<String>,
+    stream: Option<TcpStream>,
+}
+
+impl Worker {
+    fn new(shipper: &Shipper, spill: Option<Arc<Mutex<Spill>>>) -> Self {
+        Self {
+            endpoint: shipper.endpoint.clone(),
+            batch_size: shipper.batch_size,
+            interval: shipper.interval,
+            retries: shipper.retries,
+            backoff: shipper.backoff,
+            timeout: shipper.timeout,
+            spill,
+            buffer: Vec::new(),
+            stream: None,
+        }
+    }
+
+    fn run(mut self, rx: Receiver<String>) {
+        let mut last_flush = Instant::now();
+        loop {
+            let timeout = self.interval.saturating_sub(last_flush.elapsed());
+            match rx.recv_timeout(timeout) {
+                Ok(line) => {
+                    self.buffer.push(line);
+                    if self.buffer.len() < self.batch_size {
+                        continue;
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+            report(self.flush());
+            last_flush = Instant::now();
+        }
+        report(self.flush());
+    }
+
+    /// 先补发 spill, 再发送缓冲区, 失败时缓冲区写入 spill
+    fn flush(&mut self) -> io::Result<()> {
+        let batch = std::mem::take(&mut self.buffer);
+        let result = self.replay().and_then(|()| match batch.is_empty() {
+            true => Ok(()),
+            false => self.send_with_retry(&batch),
+        });
+        if let Err(err) = result {
+            if let Some(spill) = &self.spill
+                && !batch.is_empty()
+            {
+                lock(spill).append(&batch)?;
+            }
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// 按批补发本地缓冲文件
+    fn replay(&mut self) -> io::Result<()> {
+        let Some(spill) = self.spill.clone() else {
+            return Ok(());
+        };
+        loop {
+            let (lines, offset) = lock(&spill).read(self.batch_size)?;
+            if lines.is_empty() {
+                return Ok(());
+            }
+            self.send_with_retry(&lines)?;
+            lock(&spill).commit(offset)?;
+        }
+    }
+
+    fn send_with_retry(&mut self, batch: &[String]) -> io::Result<()> {
+        let mut backoff = self.backoff;
+        let mut attempt = 0;
+        loop {
+            match self.send(batch) {
+                Ok(()) => return Ok(()),
+                Err(err) if attempt >= self.retries => return Err(err),
+                Err(_) => {
+                    self.stream = None;
+                    thread::sleep(backoff);
+                    backoff *= 2;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    fn send(&mut self, batch: &[String]) -> io::Result<()> {
+        let mut body = Vec::new();
+        for line in batch {
+            body.extend_from_slice(line.as_bytes());
+            body.push(b'\n');
+        }
+        match &self.endpoint {
+            Endpoint::Tcp(addr) => {
+                let stream = match &mut self.stream {
+                    Some(stream) => stream,
+                    None => self.stream.insert(connect(addr, self.timeout)?),
+                };
+                stream.write_all(&body)?;
+                stream.flush()
+            }
+            Endpoint::Http { addr, host, path } => {
+                let mut stream = connect(addr, self.timeout)?;
+                write!(
+                    stream,
+                    "POST {path} HTTP/1.1\r\nHost: {host}\r\nContent-Type: application/x-ndjson\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                )?;
+                stream.write_all(&body)?;
+                let mut status = String::new();
+                BufReader::new(stream).read_line(&mut status)?;
+                match status.split_whitespace().nth(1) {
+                    Some(code) if code.starts_with('2') => Ok(()),
+                    _ => Err(io::Error::other(format!("日志收集端响应异常: {}", status.trim()))),
+                }
+            }
+        }
+    }
+}
+
+fn report(result: io::Result<()>) {
+    if let Err(err) = result {
+        eprintln!("推送日志失败: {err}");
+    }
+}
+
+fn connect(addr: &SocketAddr, timeout: Duration) -> io::Result<TcpStream> {
+    let stream = TcpStream::connect_timeout(addr, timeout)?;
+    stream.set_write_timeout(Some(timeout))?;
+    stream.set_read_timeout(Some(timeout))?;
+    Ok(stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Read, net::TcpListener};
+
+    use super::*;
+
+    /// 按 statuses 依次响应的 HTTP 收集端, 返回每次请求的 (状态码, body 行)
+    fn stub(listener: TcpListener, statuses: Vec<u16>) -> JoinHandle<Vec<(u16, Vec<String>)>> {
+        thread::spawn(move || {
+            let mut requests = Vec::new();
+            for status in statuses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line == "\r\n" {
+                        break;
+                    }
+                    if let Some(value) = line.strip_prefix("Content-Length: ") {
+                        length = value.trim().parse().unwrap();
+                    }
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+                let lines = String::from_utf8(body).unwrap().lines().map(String::from).collect();
+                write!(reader.get_mut(), "HTTP/1.1 {status} X\r\nContent-Length: 0\r\n\r\n").unwrap();
+                requests.push((status, lines));
+            }
+            requests
+        })
+    }
+
+    fn lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn batch_retry_spill_and_replay() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let path = std::env::temp_dir().join(format!("toolbox-spill-{}.ndjson", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let shipper = Shipper::new(Endpoint::parse(&format!("http://{addr}/logs")).unwrap())
+            .batch_size(2)
+            .retry(2, Duration::from_millis(10))
+            .spill(Some(path.clone()));
+        let spill = Arc::new(Mutex::new(Spill::new(path.clone(), shipper.spill_limit)));
+        let mut worker = Worker::new(&shipper, Some(spill));
+
+        // 第一次响应 500, 重试后成功
+        let server = stub(listener, vec![500, 200]);
+        worker.buffer = lines(&["1", "2"]);
+        worker.flush().unwrap();
+        let requests = server.join().unwrap();
+        assert_eq!(requests, [(500, lines(&["1", "2"])), (200, lines(&["1", "2"]))]);
+
+        // 收集端不可用时写入 spill
+        worker.buffer = lines(&["3", "4"]);
+        assert!(worker.flush().is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "3\n4\n");
+        worker.buffer = lines(&["5"]);
+        assert!(worker.flush().is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "3\n4\n5\n");
+
+        // 恢复后先按批补发 spill, 再发送新日志
+        let server = stub(TcpListener::bind(addr).unwrap(), vec![200, 200, 200]);
+        worker.buffer = lines(&["6"]);
+        worker.flush().unwrap();
+        let requests = server.join().unwrap();
+        assert_eq!(
+            requests,
+            [(200, lines(&["3", "4"])), (200, lines(&["5"])), (200, lines(&["6"]))]
+        );
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn tcp_batches() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut shipper = Shipper::new(Endpoint::Tcp(addr)).batch_size(2).spill(None);
+        for line in ["a", "b", "c"] {
+            shipper.push(line.into()).unwrap();
+        }
+        // 关闭时发送剩余日志
+        drop(shipper);
+
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut received = String::new();
+        stream.read_to_string(&mut received).unwrap();
+        assert_eq!(received, "a\nb\nc\n");
+    }
+
+    #[test]
+    fn spill_reads_in_chunks() {
+        let path = std::env::temp_dir().join(format!("toolbox-spill-chunks-{}.ndjson", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let mut spill = Spill::new(path.clone(), 1024);
+        spill.append(&lines(&["a", "b", "c"])).unwrap();
+
+        let (chunk, offset) = spill.read(2).unwrap();
+        assert_eq!(chunk, lines(&["a", "b"]));
+        spill.commit(offset).unwrap();
+        spill.append(&lines(&["d"])).unwrap();
+        let (chunk, offset) = spill.read(2).unwrap();
+        assert_eq!(chunk, lines(&["c", "d"]));
+        spill.commit(offset).unwrap();
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,347 @@
+use std::{
+    fs,
+    io::{self, Write},
+    net::{SocketAddr, TcpStream, UdpSocket},
+    process,
+    time::Duration,
+};
+
+use time::{
+    OffsetDateTime,
+    format_description::{BorrowedFormatItem, well_known::Rfc3339},
+    macros::format_description,
+};
+use tracing::Level;
+
+use crate::{
+    audit::AuditEntry,
+    logger::{event::Event, message::Message, output::Output},
+};
+
+/// RFC 3164 时间格式, 日期不足两位时以空格补齐
+const RFC3164: &[BorrowedFormatItem<'_>] =
+    format_description!("[month repr:short] [day padding:space] [hour]:[minute]:[second]");
+
+/// syslog 设施
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Facility {
+    User = 1,
+    Daemon = 3,
+    Auth = 4,
+    Local0 = 16,
+    Local1 = 17,
+    Local2 = 18,
+    Local3 = 19,
+    Local4 = 20,
+    Local5 = 21,
+    Local6 = 22,
+    Local7 = 23,
+}
+
+/// syslog 严重等级
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Emergency = 0,
+    Alert = 1,
+    Critical = 2,
+    Error = 3,
+    Warning = 4,
+    Notice = 5,
+    Informational = 6,
+    Debug = 7,
+}
+
+impl Severity {
+    /// 5xx 为 Error, 4xx 为 Warning, 其余为 Informational
+    pub fn from_status(status: u16) -> Self {
+        match status {
+            500.. => Self::Error,
+            400..500 => Self::Warning,
+            _ => Self::Informational,
+        }
+    }
+
+    pub fn from_level(level: Level) -> Self {
+        match level {
+            Level::ERROR => Self::Error,
+            Level::WARN => Self::Warning,
+            Level::INFO => Self::Informational,
+            Level::DEBUG | Level::TRACE => Self::Debug,
+        }
+    }
+}
+
+/// syslog 报文格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyslogFormat {
+    Rfc5424,
+    Rfc3164,
+}
+
+/// syslog 传输方式
+#[derive(Debug, Clone)]
+pub enum Transport {
+    /// Unix 数据报套接字, 通常为 `/dev/log`
+    #[cfg(unix)]
+    Unix(std::path::PathBuf),
+    Udp(SocketAddr),
+    /// TCP 使用 RFC 6587 octet-counting 分帧
+    Tcp(SocketAddr),
+}
+
+enum Conn {
+    #[cfg(unix)]
+    Unix(std::os::unix::net::UnixDatagram),
+    Udp(UdpSocket),
+    Tcp(TcpStream),
+}
+
+/// syslog 输出
+pub struct Syslog {
+    pub transport: Transport,
+    pub format: SyslogFormat,
+    pub facility: Facility,
+    pub hostname: String,
+    pub app_name: String,
+    /// TCP 连接与写入超时, 避免阻塞日志线程
+    pub timeout: Duration,
+    conn: Option<Conn>,
+}
+
+impl Syslog {
+    pub fn new(transport: Transport, format: SyslogFormat) -> Self {
+        let hostname = fs::read_to_string("/proc/sys/kernel/hostname")
+            .or_else(|_| fs::read_to_string("/etc/hostname"))
+            .map(|s| s.trim().to_string())
+            .ok()
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| "-".into());
+        Self {
+            transport,
+            format,
+            facility: Facility::User,
+            hostname,
+            app_name: "salvo".into(),
+            timeout: Duration::from_secs(3),
+            conn: None,
+        }
+    }
+
+    /// 本机 syslog 守护进程 `/dev/log`, RFC 3164 格式
+    #[cfg(unix)]
+    pub fn local() -> Self {
+        Self::new(Transport::Unix("/dev/log".into()), SyslogFormat::Rfc3164)
+    }
+
+    pub fn facility(mut self, facility: Facility) -> Self {
+        self.facility = facility;
+        self
+    }
+
+    pub fn app_name(mut self, app_name: impl Into<String>) -> Self {
+        self.app_name = app_name.into();
+        self
+    }
+
+    /// TCP 连接与写入超时, 默认 3 秒
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn connect(&self) -> io::Result<Conn> {
+        Ok(match &self.transport {
+            #[cfg(unix)]
+            Transport::Unix(path) => {
+                let socket = std::os::unix::net::UnixDatagram::unbound()?;
+                socket.connect(path)?;
+                Conn::Unix(socket)
+            }
+            Transport::Udp(addr) => {
+                let bind: SocketAddr = match addr {
+                    SocketAddr::V4(_) => ([0, 0, 0, 0], 0).into(),
+                    SocketAddr::V6(_) => ([0u16; 8], 0).into(),
+                };
+                let socket = UdpSocket::bind(bind)?;
+                socket.connect(addr)?;
+                Conn::Udp(socket)
+            }
+            Transport::Tcp(addr) => {
+                let stream = TcpStream::connect_timeout(addr, self.timeout)?;
+                stream.set_write_timeout(Some(self.timeout))?;
+                Conn::Tcp(stream)
+            }
+        })
+    }
+
+    /// 组装报文
+    fn frame(&self, severity: Severity, time: &OffsetDateTime, msg_id: &str, msg: &[u8]) -> Vec<u8> {
+        let pri = (self.facility as u8) * 8 + severity as u8;
+        let mut buf = Vec::with_capacity(msg.len() + 128);
+        match self.format {
+            SyslogFormat::Rfc5424 => {
+                let time = time.format(&Rfc3339).unwrap_or_else(|_| "-".into());
+                let pid = process::id();
+                write!(
+                    buf,
+                    "<{pri}>1 {time} {} {} {pid} {msg_id} - ",
+                    self.hostname, self.app_name
+                )
+                .ok();
+            }
+            SyslogFormat::Rfc3164 => {
+                let time = time.format(RFC3164).unwrap_or_default();
+                let pid = process::id();
+                write!(buf, "<{pri}>{time} {} {}[{pid}]: ", self.hostname, self.app_name).ok();
+            }
+        }
+        buf.extend_from_slice(msg.strip_suffix(b"\n").unwrap_or(msg));
+        buf
+    }
+
+    fn send(&mut self, frame: &[u8]) -> io::Result<()> {
+        // octet-counting 长度与报文一次写入, 部分写入失败后重连, 不会在旧连接上续写
+        let counted = match &self.transport {
+            Transport::Tcp(_) => {
+                let mut buf = format!("{} ", frame.len()).into_bytes();
+                buf.extend_from_slice(frame);
+                buf
+            }
+            _ => Vec::new(),
+        };
+        // 连接断开时重连一次
+        for retry in [false, true] {
+            let conn = match &mut self.conn {
+                Some(conn) => conn,
+                None => self.conn.insert(self.connect()?),
+            };
+            let result = match conn {
+                #[cfg(unix)]
+                Conn::Unix(socket) => socket.send(frame).map(|_| ()),
+                Conn::Udp(socket) => socket.send(frame).map(|_| ()),
+                Conn::Tcp(stream) => stream.write_all(&counted),
+            };
+            match result {
+                Err(_) if !retry => self.conn = None,
+                result => return result,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Output for Syslog {
+    fn output(&mut self, message: &Message) -> io::Result<()> {
+        let mut msg = Vec::new();
+        message.write_plain(&mut msg)?;
+        let frame = self.frame(Severity::from_status(message.status), &message.begin, "access", &msg);
+        self.send(&frame)
+    }
+
+    fn event(&mut self, event: &Event) -> io::Result<()> {
+        let mut msg = Vec::new();
+        event.write_plain(&mut msg)?;
+        let frame = self.frame(Severity::from_level(event.level), &event.time, "event", &msg);
+        self.send(&frame)
+    }
+
+    fn audit(&mut self, entry: &AuditEntry) -> io::Result<()> {
+        let frame = self.frame(Severity::Notice, &entry.time, "audit", entry.line().as_bytes());
+        self.send(&frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Read, net::TcpListener};
+
+    use time::macros::datetime;
+
+    use super::*;
+
+    const TIME: OffsetDateTime = datetime!(2024-03-05 08:09:10 UTC);
+
+    fn syslog(transport: Transport, format: SyslogFormat) -> Syslog {
+        let mut syslog = Syslog::new(transport, format)
+            .facility(Facility::Local0)
+            .app_name("app");
+        syslog.hostname = "host".into();
+        syslog
+    }
+
+    #[test]
+    fn frame_formats() {
+        let addr = "127.0.0.1:514".parse().unwrap();
+        let pid = process::id();
+
+        let frame =
+            syslog(Transport::Udp(addr), SyslogFormat::Rfc5424).frame(Severity::Error, &TIME, "access", b"GET /\n");
+        let expected = format!("<131>1 2024-03-05T08:09:10Z host app {pid} access - GET /");
+        assert_eq!(String::from_utf8(frame).unwrap(), expected);
+
+        let frame = syslog(Transport::Udp(addr), SyslogFormat::Rfc3164).frame(Severity::Notice, &TIME, "audit", b"x");
+        let expected = format!("<133>Mar  5 08:09:10 host app[{pid}]: x");
+        assert_eq!(String::from_utf8(frame).unwrap(), expected);
+    }
+
+    #[test]
+    fn udp_round_trip() {
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        server.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        let mut syslog = syslog(Transport::Udp(server.local_addr().unwrap()), SyslogFormat::Rfc3164);
+
+        let frame = syslog.frame(Severity::Informational, &TIME, "event", b"hello");
+        syslog.send(&frame).unwrap();
+        let mut buf = [0; 512];
+        let n = server.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..n], &frame[..]);
+    }
+
+    #[test]
+    fn tcp_octet_counting() {
+        let server = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut syslog = syslog(Transport::Tcp(server.local_addr().unwrap()), SyslogFormat::Rfc5424);
+
+        let first = syslog.frame(Severity::Warning, &TIME, "access", b"one");
+        let second = syslog.frame(Severity::Warning, &TIME, "access", b"two words");
+        syslog.send(&first).unwrap();
+        syslog.send(&second).unwrap();
+        drop(syslog);
+
+        let (mut stream, _) = server.accept().unwrap();
+        let mut received = Vec::new();
+        stream.read_to_end(&mut received).unwrap();
+
+        let mut expected = format!("{} ", first.len()).into_bytes();
+        expected.extend_from_slice(&first);
+        expected.extend_from_slice(format!("{} ", second.len()).as_bytes());
+        expected.extend_from_slice(&second);
+        assert_eq!(received, expected);
+    }
+
+    #[test]
+    fn tcp_connect_error() {
+        // 获取一个空闲端口后关闭监听, 连接被拒绝时返回错误而不是阻塞
+        let addr = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let mut syslog = syslog(Transport::Tcp(addr), SyslogFormat::Rfc5424).timeout(Duration::from_millis(200));
+        assert!(syslog.send(b"lost").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unix_round_trip() {
+        use std::os::unix::net::UnixDatagram;
+
+        let path = std::env::temp_dir().join(format!("toolbox-syslog-{}.sock", process::id()));
+        let _ = fs::remove_file(&path);
+        let server = UnixDatagram::bind(&path).unwrap();
+        let mut syslog = syslog(Transport::Unix(path.clone()), SyslogFormat::Rfc3164);
+
+        let frame = syslog.frame(Severity::Debug, &TIME, "event", b"local");
+        syslog.send(&frame).unwrap();
+        let mut buf = [0; 512];
+        let n = server.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..n], &frame[..]);
+        fs::remove_file(&path).ok();
+    }
+}
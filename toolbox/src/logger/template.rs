@@ -0,0 +1,175 @@
+use std::io::{self, Write};
+
+use crate::logger::{field::Field, message::Message};
+
+/// 日志格式模板, 使用 nginx 风格的 `$name` 或 `${name}` 占位符
+///
+/// 内置占位符: `$time` `$status` `$elapsed` `$elapsed_ms` `$ip` `$method` `$path`
+/// `$request_id` `$other`, 其余名称对应 [`Field`], 创建 Logger 时按输出的模板自动采集,
+/// 值不存在时输出 `-`, `$query` 为空时不输出
+#[derive(Debug, Clone)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone)]
+enum Part {
+    Literal(String),
+    Var(String),
+}
+
+impl Template {
+    /// 类似 Apache Common Log Format
+    pub const COMMON: &'static str = r#"$ip - $user_id [$time] "$method $path$query $version" $status $response_size"#;
+
+    /// 类似 Apache/nginx Combined Log Format
+    pub const COMBINED: &'static str =
+        r#"$ip - $user_id [$time] "$method $path$query $version" $status $response_size "$referer" "$user_agent""#;
+
+    pub fn new(template: &str) -> Self {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '$' {
+                literal.push(c);
+                continue;
+            }
+            let mut name = String::new();
+            if chars.next_if_eq(&'{').is_some() {
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        break;
+                    }
+                    name.push(c);
+                }
+            } else {
+                while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '_') {
+                    name.push(c);
+                }
+            }
+            if name.is_empty() {
+                literal.push('$');
+                continue;
+            }
+            if !literal.is_empty() {
+                parts.push(Part::Literal(std::mem::take(&mut literal)));
+            }
+            parts.push(Part::Var(name));
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+        Self { parts }
+    }
+
+    /// 模板中引用的字段
+    pub fn fields(&self) -> impl Iterator<Item = Field> + '_ {
+        self.parts.iter().filter_map(|part| match part {
+            Part::Var(name) => Field::from_name(name),
+            Part::Literal(_) => None,
+        })
+    }
+
+    pub fn render(&self, message: &Message, out: &mut impl Write) -> io::Result<()> {
+        for part in &self.parts {
+            match part {
+                Part::Literal(s) => out.write_all(s.as_bytes())?,
+                Part::Var(name) => match name.as_str() {
+                    "time" => write!(out, "{}", message.format())?,
+                    "status" => write!(out, "{}", message.status)?,
+                    "elapsed" => write!(out, "{:.0}", message.elapsed)?,
+                    "elapsed_ms" => write!(out, "{:.3}", message.elapsed.as_seconds_f64() * 1000.0)?,
+                    "ip" => out.write_all(message.ip.as_bytes())?,
+                    "method" => out.write_all(message.method.as_bytes())?,
+                    "path" => out.write_all(message.path.as_bytes())?,
+                    "request_id" => match &message.request_id {
+                        Some(id) => out.write_all(id.as_bytes())?,
+                        None => out.write_all(b"-")?,
+                    },
+                    "other" => out.write_all(message.other.trim().as_bytes())?,
+                    "query" => out.write_all(message.field("query").unwrap_or_default().as_bytes())?,
+                    name => out.write_all(message.field(name).unwrap_or("-").as_bytes())?,
+                },
+            }
+        }
+        writeln!(out)
+    }
+}
+
+impl From<&str> for Template {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use salvo::http::header::ACCEPT;
+    use time::macros::{datetime, offset};
+
+    use super::*;
+    use crate::logger::{
+        clock::{Clock, TimeZone},
+        field::Fields,
+        level::Level,
+    };
+
+    fn message() -> Message {
+        Message {
+            begin: datetime!(2024-06-01 12:00 UTC),
+            elapsed: time::Duration::milliseconds(5),
+            method: "GET".into(),
+            path: "/users".into(),
+            status: 200,
+            ip: "10.0.0.1".into(),
+            other: String::new(),
+            request_id: None,
+            fields: Fields(vec![("user_id".into(), "42".into())]),
+            implied: Fields(vec![
+                ("version".into(), "HTTP/1.1".into()),
+                ("response_size".into(), "12".into()),
+            ]),
+            level: Level::Info,
+            clock: Arc::new(Clock::new(TimeZone::Fixed(offset!(UTC)))),
+        }
+    }
+
+    #[test]
+    fn render_implied_fields() {
+        let message = message();
+        let mut out = Vec::new();
+        Template::new(Template::COMBINED).render(&message, &mut out).unwrap();
+        let line = String::from_utf8(out).unwrap();
+        assert!(line.starts_with("10.0.0.1 - 42 ["), "{line}");
+        assert!(line.ends_with("\"GET /users HTTP/1.1\" 200 12 \"-\" \"-\"\n"), "{line}");
+
+        // 模板引用的字段不出现在其他输出中
+        let mut out = Vec::new();
+        message.write_plain(&mut out).unwrap();
+        let line = String::from_utf8(out).unwrap();
+        assert!(line.ends_with(" user_id=\"42\"\n"), "{line}");
+        assert!(!line.contains("version"), "{line}");
+    }
+
+    #[test]
+    fn fields_from_placeholders() {
+        let fields: Vec<_> = Template::new(Template::COMBINED).fields().collect();
+        assert_eq!(
+            fields,
+            [
+                Field::UserId,
+                Field::Query,
+                Field::Version,
+                Field::ResponseSize,
+                Field::Referer,
+                Field::UserAgent
+            ]
+        );
+
+        let fields: Vec<_> = Template::new("$method ${http_accept} $time").fields().collect();
+        assert_eq!(fields, [Field::RequestHeader(ACCEPT)]);
+    }
+}
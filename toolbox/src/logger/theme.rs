@@ -0,0 +1,136 @@
+use std::{borrow::Cow, sync::OnceLock, time::Duration};
+
+use tracing::Level;
+
+/// 重置
+pub const RESET: &str = "\x1b[0m";
+/// 红色字体
+pub const RED: &str = "\x1b[31m";
+/// 绿色字体
+pub const GREEN: &str = "\x1b[32m";
+/// 黄色字体
+pub const YELLOW: &str = "\x1b[33m";
+/// 蓝色字体
+pub const BLUE: &str = "\x1b[34m";
+/// 紫色字体
+pub const PURPLE: &str = "\x1b[35m";
+
+/// 红色背景
+pub const BG_RED: &str = "\x1b[41m";
+/// 绿色背景
+pub const BG_GREEN: &str = "\x1b[42m";
+/// 黄色背景
+pub const BG_YELLOW: &str = "\x1b[43m";
+/// 蓝色背景
+pub const BG_BLUE: &str = "\x1b[44m";
+/// 紫色背景
+pub const BG_PURPLE: &str = "\x1b[45m";
+
+type Color = Cow<'static, str>;
+
+/// 彩色输出的配色方案
+#[derive(Debug, Clone)]
+pub struct Theme {
+    pub logo: Color,
+    pub ip: Color,
+    pub request_id: Color,
+    pub other: Color,
+    /// 1xx / 2xx / 3xx / 4xx 与 5xx / 其他
+    pub status: [Color; 5],
+    /// 请求方法与颜色, 未列出的方法使用 method_other
+    pub methods: Vec<(Cow<'static, str>, Color)>,
+    pub method_other: Color,
+    /// 耗时上限 (不含) 与颜色, 按升序排列, 超过全部上限时使用 elapsed_slow
+    pub elapsed: Vec<(Duration, Color)>,
+    pub elapsed_slow: Color,
+    /// 应用事件等级 ERROR / WARN / INFO / DEBUG / TRACE
+    pub levels: [Color; 5],
+    /// 应用事件来源
+    pub target: Color,
+    /// 应用事件字段
+    pub fields: Color,
+}
+
+impl Theme {
+    /// 默认配色, 供未指定配色的 `write_color` 使用
+    pub fn default_ref() -> &'static Self {
+        static DEFAULT: OnceLock<Theme> = OnceLock::new();
+        DEFAULT.get_or_init(Theme::default)
+    }
+
+    pub fn level_color(&self, level: Level) -> &str {
+        let i = match level {
+            Level::ERROR => 0,
+            Level::WARN => 1,
+            Level::INFO => 2,
+            Level::DEBUG => 3,
+            Level::TRACE => 4,
+        };
+        &self.levels[i]
+    }
+
+    pub fn status_color(&self, status: u16) -> &str {
+        let i = match status {
+            0..200 => 0,
+            200..300 => 1,
+            300..400 => 2,
+            400..600 => 3,
+            _ => 4,
+        };
+        &self.status[i]
+    }
+
+    pub fn method_color(&self, method: &str) -> &str {
+        self.methods
+            .iter()
+            .find(|(m, _)| m == method)
+            .map(|(_, c)| c.as_ref())
+            .unwrap_or(&self.method_other)
+    }
+
+    pub fn elapsed_color(&self, elapsed: Duration) -> &str {
+        self.elapsed
+            .iter()
+            .find(|(max, _)| elapsed < *max)
+            .map(|(_, c)| c.as_ref())
+            .unwrap_or(&self.elapsed_slow)
+    }
+
+    /// 设置耗时分段, 如 `[(100ms, GREEN), (500ms, YELLOW)]`, 超出部分为红色
+    pub fn elapsed_bands(mut self, bands: Vec<(Duration, &'static str)>) -> Self {
+        self.elapsed = bands.into_iter().map(|(d, c)| (d, c.into())).collect();
+        self
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        let ms = Duration::from_millis;
+        Self {
+            logo: YELLOW.into(),
+            ip: YELLOW.into(),
+            request_id: BLUE.into(),
+            other: RED.into(),
+            status: [
+                BG_BLUE.into(),
+                BG_GREEN.into(),
+                BG_YELLOW.into(),
+                BG_RED.into(),
+                BG_PURPLE.into(),
+            ],
+            methods: vec![
+                ("GET".into(), BG_GREEN.into()),
+                ("POST".into(), BG_BLUE.into()),
+                ("PATCH".into(), BG_YELLOW.into()),
+                ("PUT".into(), BG_YELLOW.into()),
+                ("DELETE".into(), BG_RED.into()),
+            ],
+            method_other: BG_PURPLE.into(),
+            elapsed: vec![(ms(10), GREEN.into()), (ms(20), BLUE.into()), (ms(30), YELLOW.into())],
+            elapsed_slow: RED.into(),
+            levels: [RED.into(), YELLOW.into(), GREEN.into(), BLUE.into(), PURPLE.into()],
+            target: BLUE.into(),
+            fields: YELLOW.into(),
+        }
+    }
+}
@@ -0,0 +1,159 @@
+/// 定义错误枚举, 为每个变体指定 HTTP 状态码, 业务码与返回给客户端的消息
+///
+/// 生成的枚举实现 `Display` 与 `From<_> for AppError`, 变体字段只写入日志;
+/// 不实现 `Error`, 否则会被当作内部错误转换
+///
+/// 只有一个字段且字段实现了 `Error` 时, 该字段作为 AppError 的 source, 保留完整的错误链;
+/// 其他字段以 Debug 格式写入日志, 每个变体最多 12 个字段
+///
+/// ```ignore
+/// app_error! {
+///     pub enum UserError {
+///         NotFound(u64) => 404, 10001, "用户不存在",
+///         Duplicate => 409, 10002, "用户已存在",
+///         Database(sqlx::Error) => 500, 10003, "数据库错误",
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! app_error {
+    (@pattern $name:ident $variant:ident [$($names:ident)*]) => {
+        $name::$variant
+    };
+    (@pattern $name:ident $variant:ident [$($names:ident)*] ($($field:ty),+)) => {
+        $crate::app_error!(@bind [] [$($names)*] $($field),+; $name::$variant)
+    };
+    (@bind [$($done:ident)*] [$next:ident $($rest:ident)*] $field:ty $(, $more:ty)*; $($path:tt)*) => {
+        $crate::app_error!(@bind [$($done)* $next] [$($rest)*] $($more),*; $($path)*)
+    };
+    (@bind [$($done:ident)*] [$($rest:ident)*]; $($path:tt)*) => {
+        $($path)*($($done),*)
+    };
+    (@source $base:ident $variant:ident [$($names:ident)*]) => {
+        $base
+    };
+    (@source $base:ident $variant:ident [$first:ident $($names:ident)*] ($field:ty)) => {{
+        #[allow(unused_imports)]
+        use $crate::error::{DebugSource as _, ErrorSource as _};
+        $base.source($crate::error::Source(::std::stringify!($variant), $first).into_source())
+    }};
+    (@source $base:ident $variant:ident [$($names:ident)*] ($($field:ty),+)) => {{
+        let fields = $crate::app_error!(@bind [] [$($names)*] $($field),+; );
+        $base.source(::std::format!("{}{:?}", ::std::stringify!($variant), fields))
+    }};
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $(
+                $(#[$vmeta:meta])*
+                $variant:ident $(($($field:ty),+ $(,)?))? => $status:expr, $code:expr, $msg:expr
+            ),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug)]
+        $vis enum $name {
+            $(
+                $(#[$vmeta])*
+                $variant $(($($field),+))?,
+            )+
+        }
+
+        impl $name {
+            pub fn status(&self) -> u16 {
+                match self {
+                    $(Self::$variant { .. } => $status,)+
+                }
+            }
+
+            pub fn code(&self) -> i64 {
+                match self {
+                    $(Self::$variant { .. } => $code,)+
+                }
+            }
+
+            pub fn message(&self) -> &'static str {
+                match self {
+                    $(Self::$variant { .. } => $msg,)+
+                }
+            }
+        }
+
+        impl ::std::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str(self.message())
+            }
+        }
+
+        impl ::std::convert::From<$name> for $crate::error::AppError {
+            fn from(err: $name) -> Self {
+                let base = $crate::error::AppError::new($crate::error::ErrorKind::from_status(err.status()), err.message())
+                    .status(err.status())
+                    .code(err.code());
+                match err {
+                    $(
+                        $crate::app_error!(@pattern $name $variant [f0 f1 f2 f3 f4 f5 f6 f7 f8 f9 f10 f11] $(($($field),+))?) => {
+                            $crate::app_error!(@source base $variant [f0 f1 f2 f3 f4 f5 f6 f7 f8 f9 f10 f11] $(($($field),+))?)
+                        }
+                    )+
+                }
+            }
+        }
+
+        impl ::std::convert::From<$name> for $crate::resp::Res {
+            fn from(err: $name) -> Self {
+                $crate::error::AppError::from(err).into()
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use crate::{error::AppError, resp::Res};
+
+    crate::app_error! {
+        enum UserError {
+            NotFound(u64) => 404, 10001, "用户不存在",
+            Duplicate => 409, 10002, "用户已存在",
+            Database(io::Error) => 500, 10003, "数据库错误",
+            Locked(u64, &'static str) => 423, 10004, "用户已锁定",
+        }
+    }
+
+    #[test]
+    fn variants() {
+        let err = UserError::NotFound(42);
+        assert_eq!((err.status(), err.code(), err.message()), (404, 10001, "用户不存在"));
+        assert_eq!(err.to_string(), "用户不存在");
+
+        let res = Res::from(UserError::Duplicate);
+        assert_eq!(res.status_code(), 409);
+        assert_eq!(res.biz_code(), 10002);
+        assert_eq!(res.info(), "用户已存在");
+    }
+
+    #[test]
+    fn source() {
+        let err = AppError::from(UserError::Duplicate);
+        assert_eq!(err.chain().count(), 0);
+
+        let err = AppError::from(UserError::NotFound(42));
+        let chain: Vec<_> = err.chain().map(|e| e.to_string()).collect();
+        assert_eq!(chain, ["NotFound(42)"]);
+
+        let err = AppError::from(UserError::Locked(7, "admin"));
+        let chain: Vec<_> = err.chain().map(|e| e.to_string()).collect();
+        assert_eq!(chain, ["Locked(7, \"admin\")"]);
+
+        // 包装的错误保留原始类型
+        let inner = io::Error::new(io::ErrorKind::TimedOut, "connection timed out");
+        let err = AppError::from(UserError::Database(inner));
+        assert!(!err.is_internal());
+        let source = err.chain().next().unwrap();
+        let io = source.downcast_ref::<io::Error>().unwrap();
+        assert_eq!(io.kind(), io::ErrorKind::TimedOut);
+    }
+}
@@ -1,6 +1,9 @@
 use std::io;
 
-use crate::resp::Res;
+use crate::{
+    error::AppError,
+    resp::{Problem, Res},
+};
 use jsonwebtoken::errors::Error as JwtError;
 use salvo::http::ParseError;
 
@@ -14,10 +17,12 @@ macro_rules! erro_from_res{
         }
     };
 
-    ($ty:path, $code:expr, $msg:expr, this) => {
+    ($ty:path, $code:expr, $key:literal => $msg:expr, this) => {
         impl From<$ty> for Res<()> {
             fn from(value: $ty) -> Self {
                 Res::new($code, format!($msg, value).into(), ())
+                    .localize($key)
+                    .arg("error", value)
             }
         }
     };
@@ -31,6 +36,27 @@ macro_rules! erro_from_res{
     };
 }
 
-erro_from_res!(io::Error, 400, "IoError: {}", this);
-erro_from_res!(JwtError, 401, "身份认证失败: {}", this);
-erro_from_res!(ParseError, 415, "数据解析失败: {}", this);
\ No newline at end of file
+/// 经由 Res 转换为 Problem
+macro_rules! problem_from_res {
+    ($($ty:path),+ $(,)?) => {
+        $(
+            impl From<$ty> for Problem {
+                fn from(value: $ty) -> Self {
+                    Res::from(value).into()
+                }
+            }
+        )+
+    };
+}
+
+erro_from_res!(JwtError, 401, "auth.invalid_token" => "身份认证失败: {}", this);
+erro_from_res!(ParseError, 415, "error.parse" => "数据解析失败: {}", this);
+
+/// IO 错误属于服务端错误, 详情不直接返回给客户端
+impl From<io::Error> for Res<()> {
+    fn from(value: io::Error) -> Self {
+        AppError::internal(value).into()
+    }
+}
+
+problem_from_res!(io::Error, JwtError, ParseError);
\ No newline at end of file
@@ -1,2 +1,3 @@
+mod app_error;
 mod err_resp;
 mod impl_resp;
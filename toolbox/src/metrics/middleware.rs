@@ -0,0 +1,111 @@
+use std::time::Instant;
+
+use salvo::{
+    Depot, FlowCtrl, Handler, Request, Response, async_trait,
+    http::{HeaderValue, header::CONTENT_TYPE},
+};
+
+use crate::metrics::registry::Registry;
+
+/// HTTP 指标采集中间件
+///
+/// ```ignore
+/// let metrics = Metrics::default();
+/// let router = Router::new()
+///     .push(Router::with_path("metrics").get(metrics.exporter()))
+///     .push(api);
+/// let service = Service::new(router).hoop(metrics);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    registry: Registry,
+}
+
+impl Metrics {
+    pub fn new(registry: Registry) -> Self {
+        Self { registry }
+    }
+
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    /// Prometheus 文本格式的指标接口
+    pub fn exporter(&self) -> MetricsExporter {
+        MetricsExporter {
+            registry: self.registry.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl Handler for Metrics {
+    async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+        let begin = Instant::now();
+        // 未匹配路由时 salvo 在调用 Service hoop 之前已设置 404/405
+        let routed = res.status_code.is_none();
+        ctrl.call_next(req, depot, res).await;
+
+        let status = res.status_code.unwrap_or_default().as_u16();
+        // 使用路由模板避免路径参数导致标签数量膨胀, 只有匹配根路由时 matched_path 才可能为空
+        let route = match req.matched_path() {
+            "" if routed && req.uri().path() == "/" => "/".to_string(),
+            "" => "unmatched".to_string(),
+            path => format!("/{path}"),
+        };
+        self.registry
+            .record(&route, req.method().as_str(), status, begin.elapsed());
+    }
+}
+
+/// 指标输出接口
+#[derive(Debug, Clone)]
+pub struct MetricsExporter {
+    registry: Registry,
+}
+
+#[async_trait]
+impl Handler for MetricsExporter {
+    async fn handle(&self, _req: &mut Request, _depot: &mut Depot, res: &mut Response, _ctrl: &mut FlowCtrl) {
+        res.headers_mut().insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("text/plain; version=0.0.4; charset=utf-8"),
+        );
+        res.write_body(self.registry.render()).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use salvo::{Router, Service, handler, test::TestClient};
+
+    use super::*;
+
+    #[handler]
+    async fn ok() -> &'static str {
+        "ok"
+    }
+
+    #[tokio::test]
+    async fn unmatched_routes() {
+        let metrics = Metrics::default();
+        let router = Router::new()
+            .get(ok)
+            .push(Router::with_path("users/{id}").get(ok));
+        let service = Service::new(router).hoop(metrics.clone());
+        for (method, path) in [("GET", "/"), ("GET", "/users/1"), ("GET", "/missing"), ("POST", "/users/1")] {
+            let client = match method {
+                "GET" => TestClient::get(format!("http://127.0.0.1{path}")),
+                _ => TestClient::post(format!("http://127.0.0.1{path}")),
+            };
+            client.send(&service).await;
+        }
+
+        let text = metrics.registry().render();
+        let count = |labels: &str| text.contains(&format!("http_requests_total{{{labels}}} 1"));
+        assert!(count(r#"route="/",method="GET",status="2xx""#), "{text}");
+        assert!(count(r#"route="/users/{id}",method="GET",status="2xx""#), "{text}");
+        assert!(count(r#"route="unmatched",method="GET",status="4xx""#), "{text}");
+        assert!(count(r#"route="unmatched",method="POST",status="4xx""#), "{text}");
+    }
+}
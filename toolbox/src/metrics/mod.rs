@@ -0,0 +1,5 @@
+pub mod middleware;
+pub mod registry;
+
+pub use middleware::*;
+pub use registry::Registry;
@@ -0,0 +1,135 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// 默认直方图桶, 单位秒
+pub const DEFAULT_BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Key {
+    route: String,
+    method: String,
+    class: &'static str,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Series {
+    count: u64,
+    errors: u64,
+    buckets: Vec<u64>,
+    sum: f64,
+}
+
+/// 按路由模板, 请求方法, 状态码类别聚合的 HTTP 指标
+#[derive(Debug, Clone)]
+pub struct Registry {
+    buckets: Arc<Vec<f64>>,
+    series: Arc<Mutex<BTreeMap<Key, Series>>>,
+}
+
+impl Registry {
+    pub fn new(buckets: Vec<f64>) -> Self {
+        Self {
+            buckets: Arc::new(buckets),
+            series: Arc::default(),
+        }
+    }
+
+    /// 记录一次请求, 5xx 计入错误数
+    pub fn record(&self, route: &str, method: &str, status: u16, elapsed: Duration) {
+        let key = Key {
+            route: route.into(),
+            method: method.into(),
+            class: status_class(status),
+        };
+        let seconds = elapsed.as_secs_f64();
+        let Ok(mut series) = self.series.lock() else {
+            return;
+        };
+        let series = series.entry(key).or_insert_with(|| Series {
+            buckets: vec![0; self.buckets.len()],
+            ..Default::default()
+        });
+        series.count += 1;
+        series.sum += seconds;
+        if status >= 500 {
+            series.errors += 1;
+        }
+        for (i, le) in self.buckets.iter().enumerate() {
+            if seconds <= *le {
+                series.buckets[i] += 1;
+            }
+        }
+    }
+
+    /// 输出 Prometheus 文本格式
+    pub fn render(&self) -> String {
+        let Ok(series) = self.series.lock() else {
+            return String::new();
+        };
+        let mut out = String::new();
+        let labels = |key: &Key| {
+            format!(
+                r#"route="{}",method="{}",status="{}""#,
+                escape(&key.route),
+                escape(&key.method),
+                key.class
+            )
+        };
+
+        out.push_str("# HELP http_requests_total Total number of HTTP requests.\n");
+        out.push_str("# TYPE http_requests_total counter\n");
+        for (key, s) in series.iter() {
+            writeln!(out, "http_requests_total{{{}}} {}", labels(key), s.count).ok();
+        }
+
+        out.push_str("# HELP http_request_errors_total Total number of HTTP requests answered with 5xx.\n");
+        out.push_str("# TYPE http_request_errors_total counter\n");
+        for (key, s) in series.iter() {
+            writeln!(out, "http_request_errors_total{{{}}} {}", labels(key), s.errors).ok();
+        }
+
+        out.push_str("# HELP http_request_duration_seconds HTTP request latency.\n");
+        out.push_str("# TYPE http_request_duration_seconds histogram\n");
+        for (key, s) in series.iter() {
+            let labels = labels(key);
+            for (le, n) in self.buckets.iter().zip(&s.buckets) {
+                writeln!(out, r#"http_request_duration_seconds_bucket{{{labels},le="{le}"}} {n}"#).ok();
+            }
+            writeln!(
+                out,
+                r#"http_request_duration_seconds_bucket{{{labels},le="+Inf"}} {}"#,
+                s.count
+            )
+            .ok();
+            writeln!(out, "http_request_duration_seconds_sum{{{labels}}} {}", s.sum).ok();
+            writeln!(out, "http_request_duration_seconds_count{{{labels}}} {}", s.count).ok();
+        }
+        out
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::new(DEFAULT_BUCKETS.to_vec())
+    }
+}
+
+fn status_class(status: u16) -> &'static str {
+    match status {
+        100..200 => "1xx",
+        200..300 => "2xx",
+        300..400 => "3xx",
+        400..500 => "4xx",
+        500..600 => "5xx",
+        _ => "other",
+    }
+}
+
+/// 转义标签值中的 `\` `"` 与换行
+fn escape(value: &str) -> String {
+    value.replace('\\', r"\\").replace('"', r#"\""#).replace('\n', r"\n")
+}
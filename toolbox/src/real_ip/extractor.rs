@@ -0,0 +1,29 @@
+use std::net::IpAddr;
+
+use derive_more::{Deref, Display};
+use salvo::{Extractible, Request, Writer};
+
+use crate::{global::METADATE, res, resp::Res};
+
+/// 客户端真实 IP
+///
+/// 需要先挂载 [`RealIpResolver`](super::RealIpResolver), 否则取连接的对端地址
+#[derive(Debug, Clone, Copy, Deref, Display, PartialEq, Eq)]
+pub struct RealIp(pub IpAddr);
+
+impl<'ex> Extractible<'ex> for RealIp {
+    fn metadata() -> &'ex salvo::extract::Metadata {
+        &METADATE
+    }
+
+    async fn extract(req: &'ex mut Request) -> Result<Self, impl Writer + Send + std::fmt::Debug + 'static> {
+        if let Some(ip) = req.extensions().get::<Self>() {
+            return Ok(*ip);
+        }
+        req.remote_addr()
+            .clone()
+            .into_std()
+            .map(|addr| Self(addr.ip()))
+            .ok_or_else(|| -> Res { res!(400, "无法获取客户端 IP").localize("real_ip.unknown") })
+    }
+}
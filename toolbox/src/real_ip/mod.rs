@@ -0,0 +1,5 @@
+pub mod extractor;
+pub mod resolver;
+
+pub use extractor::*;
+pub use resolver::*;
@@ -0,0 +1,235 @@
+use std::{net::IpAddr, sync::Arc};
+
+use ipnet::IpNet;
+use salvo::{
+    Depot, FlowCtrl, Handler, Request, Response, async_trait,
+    http::{
+        HeaderMap,
+        header::{AsHeaderName, FORWARDED},
+    },
+};
+
+use crate::real_ip::extractor::RealIp;
+
+/// 携带客户端 IP 的代理请求头
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProxyHeader {
+    /// RFC 7239 `Forwarded: for=...`
+    Forwarded,
+    /// `X-Forwarded-For`
+    XForwardedFor,
+    /// `X-Real-IP`
+    XRealIp,
+}
+
+/// 真实 IP 解析器
+///
+/// 仅当连接对端属于受信任代理时才读取代理请求头, 从右往左跳过受信任代理, 第一个不受信任的地址即客户端 IP
+///
+/// 默认只读取 `X-Forwarded-For`, 代理通常只改写其中一个请求头, 其余请求头会被原样透传,
+/// 需通过 [`RealIpResolver::headers`] 设置为代理实际写入的请求头
+#[derive(Debug, Clone)]
+pub struct RealIpResolver {
+    trusted: Arc<Vec<IpNet>>,
+    headers: Vec<ProxyHeader>,
+}
+
+impl RealIpResolver {
+    /// trusted 为受信任代理的 CIDR 列表, 如 `10.0.0.0/8`, 单个地址视为 /32 或 /128
+    pub fn new<I, S>(trusted: I) -> Result<Self, ipnet::AddrParseError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let trusted = trusted
+            .into_iter()
+            .map(|s| {
+                let s = s.as_ref();
+                s.parse::<IpNet>()
+                    .or_else(|e| s.parse::<IpAddr>().map(IpNet::from).map_err(|_| e))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            trusted: Arc::new(trusted),
+            headers: vec![ProxyHeader::XForwardedFor],
+        })
+    }
+
+    /// 设置读取的请求头及优先级, 只使用第一个存在的请求头
+    pub fn headers(mut self, headers: Vec<ProxyHeader>) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    pub fn is_trusted(&self, ip: &IpAddr) -> bool {
+        self.trusted.iter().any(|net| net.contains(ip))
+    }
+
+    /// 解析客户端 IP, 对端不是 IP 地址 (如 Unix Socket) 时返回 None
+    pub fn resolve(&self, req: &Request) -> Option<IpAddr> {
+        let peer = req.remote_addr().clone().into_std()?.ip();
+        if !self.is_trusted(&peer) {
+            return Some(peer);
+        }
+        let chain = self.headers.iter().find_map(|header| match header {
+            ProxyHeader::Forwarded => forwarded(req.headers()),
+            ProxyHeader::XForwardedFor => list(req.headers(), "x-forwarded-for"),
+            ProxyHeader::XRealIp => list(req.headers(), "x-real-ip"),
+        });
+        Some(chain.map_or(peer, |chain| self.pick(peer, chain)))
+    }
+
+    /// 从右往左取第一个不受信任的地址, 全部受信任时取最左侧地址
+    ///
+    /// 遇到无法识别的地址 (如 `unknown` 或混淆标识) 时停止, 其左侧内容不可信, 取最后一个受信任的地址
+    fn pick(&self, peer: IpAddr, chain: Vec<Option<IpAddr>>) -> IpAddr {
+        let mut last = peer;
+        for ip in chain.into_iter().rev() {
+            let Some(ip) = ip else {
+                break;
+            };
+            if !self.is_trusted(&ip) {
+                return ip;
+            }
+            last = ip;
+        }
+        last
+    }
+}
+
+#[async_trait]
+impl Handler for RealIpResolver {
+    async fn handle(&self, req: &mut Request, _depot: &mut Depot, _res: &mut Response, _ctrl: &mut FlowCtrl) {
+        if let Some(ip) = self.resolve(req) {
+            req.extensions_mut().insert(RealIp(ip));
+        }
+    }
+}
+
+/// 按逗号拆分请求头, 可能出现多个同名请求头, 无法解码的值视为一个无法识别的节点
+fn hops(headers: &HeaderMap, name: impl AsHeaderName) -> Option<Vec<Option<&str>>> {
+    let chain: Vec<_> = headers
+        .get_all(name)
+        .iter()
+        .flat_map(|v| match v.to_str() {
+            Ok(v) => v.split(',').map(Some).collect(),
+            Err(_) => vec![None],
+        })
+        .collect();
+    (!chain.is_empty()).then_some(chain)
+}
+
+/// 读取逗号分隔的地址列表
+fn list(headers: &HeaderMap, name: &str) -> Option<Vec<Option<IpAddr>>> {
+    let chain = hops(headers, name)?;
+    Some(chain.into_iter().map(|hop| parse_node(hop?.trim())).collect())
+}
+
+/// 读取 RFC 7239 Forwarded 中的 for 参数, 缺少 for 的节点视为无法识别
+fn forwarded(headers: &HeaderMap) -> Option<Vec<Option<IpAddr>>> {
+    let chain = hops(headers, FORWARDED)?;
+    let node = |element: &str| {
+        element.split(';').find_map(|pair| {
+            let (key, value) = pair.trim().split_once('=')?;
+            key.trim()
+                .eq_ignore_ascii_case("for")
+                .then(|| parse_node(value.trim().trim_matches('"')))
+        })?
+    };
+    Some(chain.into_iter().map(|element| node(element?)).collect())
+}
+
+/// 解析 `1.2.3.4`, `1.2.3.4:80`, `[::1]:80`, `::1` 形式的节点
+fn parse_node(node: &str) -> Option<IpAddr> {
+    if let Ok(ip) = node.parse() {
+        return Some(ip);
+    }
+    if let Some(rest) = node.strip_prefix('[') {
+        return rest.split_once(']')?.0.parse().ok();
+    }
+    node.rsplit_once(':')?.0.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use salvo::http::HeaderValue;
+
+    use super::*;
+
+    fn request(peer: &str, headers: &[(&'static str, &str)]) -> Request {
+        let mut req = Request::default();
+        *req.remote_addr_mut() = peer.parse::<SocketAddr>().unwrap().into();
+        for (name, value) in headers {
+            req.headers_mut()
+                .append(*name, HeaderValue::from_bytes(value.as_bytes()).unwrap());
+        }
+        req
+    }
+
+    fn resolve(resolver: &RealIpResolver, peer: &str, headers: &[(&'static str, &str)]) -> String {
+        resolver.resolve(&request(peer, headers)).unwrap().to_string()
+    }
+
+    #[test]
+    fn untrusted_peer_ignores_headers() {
+        let resolver = RealIpResolver::new(["10.0.0.0/8"]).unwrap();
+        let ip = resolve(&resolver, "1.1.1.1:80", &[("x-forwarded-for", "2.2.2.2")]);
+        assert_eq!(ip, "1.1.1.1");
+    }
+
+    #[test]
+    fn x_forwarded_for_skips_trusted_hops() {
+        let resolver = RealIpResolver::new(["10.0.0.0/8", "192.168.1.1"]).unwrap();
+        let headers = [
+            ("x-forwarded-for", "3.3.3.3, 2.2.2.2"),
+            ("x-forwarded-for", "192.168.1.1, 10.1.1.1"),
+        ];
+        assert_eq!(resolve(&resolver, "10.0.0.1:80", &headers), "2.2.2.2");
+
+        // 全部受信任时取最左侧地址
+        let headers = [("x-forwarded-for", "10.2.2.2, 10.1.1.1")];
+        assert_eq!(resolve(&resolver, "10.0.0.1:80", &headers), "10.2.2.2");
+    }
+
+    #[test]
+    fn default_reads_only_x_forwarded_for() {
+        let resolver = RealIpResolver::new(["10.0.0.0/8"]).unwrap();
+        let headers = [("forwarded", "for=6.6.6.6"), ("x-real-ip", "7.7.7.7")];
+        assert_eq!(resolve(&resolver, "10.0.0.1:80", &headers), "10.0.0.1");
+
+        let resolver = resolver.headers(vec![ProxyHeader::Forwarded, ProxyHeader::XForwardedFor]);
+        let headers = [("forwarded", "for=6.6.6.6"), ("x-forwarded-for", "7.7.7.7")];
+        assert_eq!(resolve(&resolver, "10.0.0.1:80", &headers), "6.6.6.6");
+    }
+
+    #[test]
+    fn forwarded_nodes() {
+        let resolver = RealIpResolver::new(["10.0.0.0/8"])
+            .unwrap()
+            .headers(vec![ProxyHeader::Forwarded]);
+        let headers = [("forwarded", r#"for="[2001:db8::1]:443";proto=https, For=10.1.1.1:80"#)];
+        assert_eq!(resolve(&resolver, "10.0.0.1:80", &headers), "2001:db8::1");
+    }
+
+    #[test]
+    fn unparseable_hop_is_terminal() {
+        let resolver = RealIpResolver::new(["10.0.0.0/8"])
+            .unwrap()
+            .headers(vec![ProxyHeader::Forwarded, ProxyHeader::XForwardedFor]);
+        // 无法识别的节点左侧不可信, 也不回退到优先级更低的请求头
+        let headers = [
+            ("forwarded", "for=5.5.5.5, for=_hidden, for=10.1.1.1"),
+            ("x-forwarded-for", "8.8.8.8"),
+        ];
+        assert_eq!(resolve(&resolver, "10.0.0.1:80", &headers), "10.1.1.1");
+
+        let headers = [("forwarded", "proto=https"), ("x-forwarded-for", "8.8.8.8")];
+        assert_eq!(resolve(&resolver, "10.0.0.1:80", &headers), "10.0.0.1");
+
+        let resolver = RealIpResolver::new(["10.0.0.0/8"]).unwrap();
+        let headers = [("x-forwarded-for", "5.5.5.5, unknown")];
+        assert_eq!(resolve(&resolver, "10.0.0.1:80", &headers), "10.0.0.1");
+    }
+}
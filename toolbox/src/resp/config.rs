@@ -0,0 +1,133 @@
+use std::sync::OnceLock;
+
+use crate::resp::{envelope::Envelope, format::Format};
+
+static CONFIG: OnceLock<RespConfig> = OnceLock::new();
+
+/// HTTP 状态码策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StatusPolicy {
+    /// HTTP 状态码与 status 一致, 不合法时为 400
+    #[default]
+    Mirror,
+    /// 始终返回 200, 结果仅体现在 code 中
+    AlwaysOk,
+}
+
+/// 错误响应格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ErrorFormat {
+    /// 按 Envelope 结构输出的信封
+    #[default]
+    Envelope,
+    /// RFC 9457 `application/problem+json`
+    Problem,
+}
+
+/// 内部错误详情的暴露策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exposure {
+    /// 只返回通用提示与请求 ID, 详情写入日志, 5xx 响应的 info 也替换为通用提示
+    Production,
+    /// 同时返回详情
+    Development,
+}
+
+impl Exposure {
+    /// 读取环境变量 APP_ENV, 未设置时 debug 构建为 Development, release 构建为 Production
+    pub fn from_env() -> Self {
+        match std::env::var("APP_ENV").as_deref() {
+            Ok("production" | "prod") => Self::Production,
+            Ok("development" | "dev") => Self::Development,
+            _ if cfg!(debug_assertions) => Self::Development,
+            _ => Self::Production,
+        }
+    }
+}
+
+/// 全局响应配置
+#[derive(Debug, Clone)]
+pub struct RespConfig {
+    pub status: StatusPolicy,
+    pub error_format: ErrorFormat,
+    /// 可协商的序列化格式, 按优先级排列, 默认为已启用的全部格式
+    pub formats: Vec<Format>,
+    /// 未指定时的分页大小
+    pub default_page_size: u64,
+    /// 允许的最大分页大小
+    pub max_page_size: u64,
+    pub exposure: Exposure,
+    /// 无可接受的格式时使用 JSON 而非返回 406, 错误响应总是回退到 JSON
+    pub fallback_json: bool,
+    /// 信封字段名与附加字段
+    pub envelope: Box<Envelope>,
+}
+
+impl Default for RespConfig {
+    fn default() -> Self {
+        Self {
+            status: StatusPolicy::default(),
+            error_format: ErrorFormat::default(),
+            formats: Format::all(),
+            default_page_size: 20,
+            max_page_size: 100,
+            exposure: Exposure::from_env(),
+            fallback_json: false,
+            envelope: Box::default(),
+        }
+    }
+}
+
+impl RespConfig {
+    pub fn status(mut self, status: StatusPolicy) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn error_format(mut self, error_format: ErrorFormat) -> Self {
+        self.error_format = error_format;
+        self
+    }
+
+    pub fn exposure(mut self, exposure: Exposure) -> Self {
+        self.exposure = exposure;
+        self
+    }
+
+    pub fn fallback_json(mut self, fallback_json: bool) -> Self {
+        self.fallback_json = fallback_json;
+        self
+    }
+
+    pub fn envelope(mut self, envelope: Envelope) -> Self {
+        self.envelope = Box::new(envelope);
+        self
+    }
+
+    /// 设置默认与最大分页大小
+    pub fn page_size(mut self, default: u64, max: u64) -> Self {
+        self.max_page_size = max.max(1);
+        self.default_page_size = default.clamp(1, self.max_page_size);
+        self
+    }
+
+    /// 设置可协商的格式, 为空时只使用 JSON
+    pub fn formats(mut self, formats: Vec<Format>) -> Self {
+        self.formats = if formats.is_empty() {
+            vec![Format::Json]
+        } else {
+            formats
+        };
+        self
+    }
+}
+
+/// 设置全局响应配置, 只能设置一次, 需在启动服务之前调用
+pub fn install(config: RespConfig) -> Result<(), RespConfig> {
+    CONFIG.set(config)
+}
+
+/// 全局响应配置, 未设置时使用默认值
+pub fn config() -> &'static RespConfig {
+    CONFIG.get_or_init(RespConfig::default)
+}
@@ -0,0 +1,381 @@
+use std::{fmt, sync::Arc};
+
+use salvo::Request;
+use serde::{
+    Serialize, Serializer,
+    ser::{Impossible, SerializeStruct},
+};
+use serde_json::Value;
+use time::format_description::well_known::Rfc3339;
+
+use crate::{logger::clock::clock, resp::Res};
+
+/// 由请求计算的附加字段
+type Compute = Arc<dyn Fn(&Request) -> (&'static str, Value) + Send + Sync>;
+
+/// 时间戳格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Timestamp {
+    /// RFC 3339 字符串
+    #[default]
+    Rfc3339,
+    /// Unix 毫秒
+    Millis,
+}
+
+/// 响应信封结构, 默认为 `{info, code, data}`, 错误响应附带 request_id
+///
+/// ```ignore
+/// let envelope = Envelope::default()
+///     .info("message")
+///     .request_id_always(true)
+///     .timestamp("time", Timestamp::Millis)
+///     .field(|req| ("path", req.uri().path().into()));
+/// ```
+#[derive(Clone)]
+pub struct Envelope {
+    info: &'static str,
+    code: &'static str,
+    data: &'static str,
+    request_id: &'static str,
+    /// 成功响应是否也附带请求 ID
+    request_id_always: bool,
+    timestamp: Option<(&'static str, Timestamp)>,
+    success: Option<&'static str>,
+    omit_unit: bool,
+    fields: Vec<Compute>,
+}
+
+impl fmt::Debug for Envelope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Envelope")
+            .field("info", &self.info)
+            .field("code", &self.code)
+            .field("data", &self.data)
+            .field("request_id", &self.request_id)
+            .field("request_id_always", &self.request_id_always)
+            .field("timestamp", &self.timestamp)
+            .field("success", &self.success)
+            .field("omit_unit", &self.omit_unit)
+            .field("fields", &self.fields.len())
+            .finish()
+    }
+}
+
+impl Default for Envelope {
+    fn default() -> Self {
+        Self {
+            info: "info",
+            code: "code",
+            data: "data",
+            request_id: "request_id",
+            request_id_always: false,
+            timestamp: None,
+            success: None,
+            omit_unit: false,
+            fields: Vec::new(),
+        }
+    }
+}
+
+impl Envelope {
+    /// 提示信息字段名, 如 `message`
+    pub fn info(mut self, name: &'static str) -> Self {
+        self.info = name;
+        self
+    }
+
+    /// 业务码字段名, 如 `status`
+    pub fn code(mut self, name: &'static str) -> Self {
+        self.code = name;
+        self
+    }
+
+    /// 数据字段名, 如 `result`
+    pub fn data(mut self, name: &'static str) -> Self {
+        self.data = name;
+        self
+    }
+
+    /// 请求 ID 字段名
+    pub fn request_id(mut self, name: &'static str) -> Self {
+        self.request_id = name;
+        self
+    }
+
+    /// 成功响应是否也附带请求 ID, 默认只有错误响应附带
+    pub fn request_id_always(mut self, always: bool) -> Self {
+        self.request_id_always = always;
+        self
+    }
+
+    /// 附加响应时间字段
+    pub fn timestamp(mut self, name: &'static str, format: Timestamp) -> Self {
+        self.timestamp = Some((name, format));
+        self
+    }
+
+    /// 附加是否成功字段, 值为 `!is_error()`
+    pub fn success(mut self, name: &'static str) -> Self {
+        self.success = Some(name);
+        self
+    }
+
+    /// data 为 `()` 时省略该字段
+    pub fn omit_unit(mut self, omit: bool) -> Self {
+        self.omit_unit = omit;
+        self
+    }
+
+    /// 附加由请求计算的字段, 写入响应时调用, 与其他字段同名时重复输出
+    pub fn field(mut self, compute: impl Fn(&Request) -> (&'static str, Value) + Send + Sync + 'static) -> Self {
+        self.fields.push(Arc::new(compute));
+        self
+    }
+
+    /// 计算附加字段
+    pub(crate) fn compute(&self, req: &Request) -> Box<[(&'static str, Value)]> {
+        self.fields.iter().map(|compute| compute(req)).collect()
+    }
+
+    /// 是否为该响应附带请求 ID
+    pub(crate) fn with_request_id(&self, is_error: bool) -> bool {
+        is_error || self.request_id_always
+    }
+
+    /// 按信封结构序列化 Res
+    pub(crate) fn serialize<T: Serialize, S: Serializer>(
+        &self,
+        res: &Res<T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let omit_data = self.omit_unit && is_unit(&res.data);
+        let len = 2
+            + usize::from(!omit_data)
+            + usize::from(res.request_id.is_some())
+            + usize::from(self.timestamp.is_some())
+            + usize::from(self.success.is_some())
+            + res.fields.len();
+        let mut state = serializer.serialize_struct("Res", len)?;
+        state.serialize_field(self.info, &res.info)?;
+        state.serialize_field(self.code, &res.biz_code())?;
+        if !omit_data {
+            state.serialize_field(self.data, &res.data)?;
+        }
+        if let Some(success) = self.success {
+            state.serialize_field(success, &!res.is_error())?;
+        }
+        if let Some((field, format)) = self.timestamp {
+            let now = clock().now();
+            match format {
+                Timestamp::Rfc3339 => {
+                    let now = now.format(&Rfc3339).map_err(serde::ser::Error::custom)?;
+                    state.serialize_field(field, &now)?;
+                }
+                Timestamp::Millis => {
+                    let millis = (now.unix_timestamp_nanos() / 1_000_000) as i64;
+                    state.serialize_field(field, &millis)?;
+                }
+            }
+        }
+        if let Some(id) = &res.request_id {
+            state.serialize_field(self.request_id, id)?;
+        }
+        for (name, value) in &res.fields {
+            state.serialize_field(name, value)?;
+        }
+        state.end()
+    }
+}
+
+/// 值是否序列化为 unit, 如 `()`
+fn is_unit<T: Serialize>(value: &T) -> bool {
+    value.serialize(UnitProbe).is_ok()
+}
+
+/// 只接受 unit 的序列化器
+struct UnitProbe;
+
+macro_rules! reject {
+    ($($method:ident: $ty:ty),* $(,)?) => {
+        $(fn $method(self, _: $ty) -> Result<(), fmt::Error> {
+            Err(fmt::Error)
+        })*
+    };
+}
+
+impl Serializer for UnitProbe {
+    type Ok = ();
+    type Error = fmt::Error;
+    type SerializeSeq = Impossible<(), fmt::Error>;
+    type SerializeTuple = Impossible<(), fmt::Error>;
+    type SerializeTupleStruct = Impossible<(), fmt::Error>;
+    type SerializeTupleVariant = Impossible<(), fmt::Error>;
+    type SerializeMap = Impossible<(), fmt::Error>;
+    type SerializeStruct = Impossible<(), fmt::Error>;
+    type SerializeStructVariant = Impossible<(), fmt::Error>;
+
+    reject! {
+        serialize_bool: bool,
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_f32: f32,
+        serialize_f64: f64,
+        serialize_char: char,
+        serialize_str: &str,
+        serialize_bytes: &[u8],
+        serialize_unit_struct: &'static str,
+    }
+
+    fn serialize_unit(self) -> Result<(), fmt::Error> {
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), fmt::Error> {
+        Err(fmt::Error)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, _: &T) -> Result<(), fmt::Error> {
+        Err(fmt::Error)
+    }
+
+    fn serialize_unit_variant(self, _: &'static str, _: u32, _: &'static str) -> Result<(), fmt::Error> {
+        Err(fmt::Error)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _: &'static str, _: &T) -> Result<(), fmt::Error> {
+        Err(fmt::Error)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<(), fmt::Error> {
+        Err(fmt::Error)
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, fmt::Error> {
+        Err(fmt::Error)
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, fmt::Error> {
+        Err(fmt::Error)
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeTupleStruct, fmt::Error> {
+        Err(fmt::Error)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, fmt::Error> {
+        Err(fmt::Error)
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, fmt::Error> {
+        Err(fmt::Error)
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeStruct, fmt::Error> {
+        Err(fmt::Error)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, fmt::Error> {
+        Err(fmt::Error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn to_value<T: Serialize>(envelope: &Envelope, res: &Res<T>) -> Value {
+        envelope.serialize(res, serde_json::value::Serializer).unwrap()
+    }
+
+    #[test]
+    fn default() {
+        let envelope = Envelope::default();
+        let res = Res::new(200, "OK".into(), vec![1, 2]);
+        assert_eq!(
+            to_value(&envelope, &res),
+            json!({"info": "OK", "code": 200, "data": [1, 2]})
+        );
+
+        let mut res = Res::new(10001, "余额不足".into(), ());
+        res.request_id = Some("01HX".into());
+        assert_eq!(
+            to_value(&envelope, &res),
+            json!({"info": "余额不足", "code": 10001, "data": null, "request_id": "01HX"})
+        );
+        assert!(envelope.with_request_id(true));
+        assert!(!envelope.with_request_id(false));
+    }
+
+    #[test]
+    fn custom() {
+        let envelope = Envelope::default()
+            .info("message")
+            .code("status")
+            .data("result")
+            .request_id("trace_id")
+            .request_id_always(true)
+            .success("ok")
+            .omit_unit(true)
+            .field(|req| ("path", req.uri().path().into()));
+        assert!(envelope.with_request_id(false));
+
+        let mut req = Request::new();
+        *req.uri_mut() = "/users".parse().unwrap();
+        let mut res = Res::new(201, "Created".into(), ());
+        res.request_id = Some("01HX".into());
+        res.fields = envelope.compute(&req);
+        assert_eq!(
+            to_value(&envelope, &res),
+            json!({"message": "Created", "status": 201, "ok": true, "trace_id": "01HX", "path": "/users"})
+        );
+
+        // 只省略 unit, None 与其他空值保留
+        let res = Res::new(404, "Not Found".into(), None::<u32>);
+        assert_eq!(
+            to_value(&envelope, &res),
+            json!({"message": "Not Found", "status": 404, "result": null, "ok": false})
+        );
+        assert!(is_unit(&()));
+        assert!(!is_unit(&0));
+        assert!(!is_unit(&""));
+        assert!(!is_unit(&Vec::<u8>::new()));
+    }
+
+    #[test]
+    fn timestamp() {
+        let res = Res::new(200, "OK".into(), ());
+        let value = to_value(&Envelope::default().timestamp("time", Timestamp::Millis), &res);
+        assert!(value["time"].as_i64().is_some_and(|millis| millis > 0), "{value}");
+
+        let value = to_value(&Envelope::default().timestamp("time", Timestamp::Rfc3339), &res);
+        let time = value["time"].as_str().unwrap();
+        assert!(time::OffsetDateTime::parse(time, &Rfc3339).is_ok(), "{time}");
+    }
+}
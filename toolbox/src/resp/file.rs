@@ -0,0 +1,149 @@
+use std::{io, path::PathBuf};
+
+use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
+use salvo::{
+    Depot, Request, Response, Writer, async_trait,
+    fs::NamedFile,
+    http::{
+        HeaderValue,
+        header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+        mime,
+    },
+};
+
+use crate::{error::AppError, res, resp::Res};
+
+enum Source {
+    Path(PathBuf),
+    Bytes(Vec<u8>),
+}
+
+/// 文件下载响应
+///
+/// 文件无法打开时返回错误的 Res, 文件支持 Range 与 ETag
+///
+/// ```ignore
+/// async fn download() -> Attachment {
+///     Attachment::file("exports/report.csv").name("报表.csv")
+/// }
+/// ```
+pub struct Attachment {
+    source: Source,
+    name: Option<String>,
+    content_type: Option<String>,
+    inline: bool,
+}
+
+impl Attachment {
+    /// 磁盘文件, 默认以文件名作为下载名
+    pub fn file(path: impl Into<PathBuf>) -> Self {
+        Self {
+            source: Source::Path(path.into()),
+            name: None,
+            content_type: None,
+            inline: false,
+        }
+    }
+
+    /// 内存数据
+    pub fn bytes(data: impl Into<Vec<u8>>, name: impl Into<String>) -> Self {
+        Self {
+            source: Source::Bytes(data.into()),
+            name: Some(name.into()),
+            content_type: None,
+            inline: false,
+        }
+    }
+
+    /// 下载文件名
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// 磁盘文件默认按扩展名推断, 内存数据默认为 application/octet-stream
+    pub fn content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+
+    /// 在浏览器中直接打开而不是下载
+    pub fn inline(mut self) -> Self {
+        self.inline = true;
+        self
+    }
+
+    fn disposition(&self, name: &str) -> HeaderValue {
+        let kind = if self.inline { "inline" } else { "attachment" };
+        // filename 为 ASCII 回退, filename* 为 RFC 5987 编码的原始文件名
+        let fallback: String = name
+            .chars()
+            .map(|c| match c {
+                ' '..='~' if c != '"' && c != '\\' => c,
+                _ => '_',
+            })
+            .collect();
+        let encoded = utf8_percent_encode(name, NON_ALPHANUMERIC);
+        HeaderValue::from_str(&format!("{kind}; filename=\"{fallback}\"; filename*=UTF-8''{encoded}"))
+            .unwrap_or_else(|_| HeaderValue::from_static("attachment"))
+    }
+}
+
+fn open_error(err: salvo::Error) -> Res {
+    match err {
+        salvo::Error::Io(err) if err.kind() == io::ErrorKind::NotFound => {
+            res!(404, "文件不存在").localize("file.not_found")
+        }
+        salvo::Error::Io(err) if err.kind() == io::ErrorKind::PermissionDenied => {
+            res!(403, "无权访问文件").localize("file.forbidden")
+        }
+        salvo::Error::Io(err) => err.into(),
+        err => AppError::internal(err).into(),
+    }
+}
+
+#[async_trait]
+impl Writer for Attachment {
+    async fn write(self, req: &mut Request, depot: &mut Depot, res: &mut Response) {
+        let content_type = match self.content_type.as_deref().map(str::parse).transpose() {
+            Ok(content_type) => content_type,
+            Err(e) => {
+                return Res::from(AppError::internal(format!("invalid Content-Type: {e}")))
+                    .write(req, depot, res)
+                    .await;
+            }
+        };
+        match &self.source {
+            Source::Path(path) => {
+                let mut builder = NamedFile::builder(path);
+                if let Some(content_type) = content_type {
+                    builder = builder.content_type(content_type);
+                }
+                let mut file = match builder.build().await {
+                    Ok(file) => file,
+                    Err(err) => return open_error(err).write(req, depot, res).await,
+                };
+                let name = match &self.name {
+                    Some(name) => name.clone(),
+                    None => path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_else(|| "file".into()),
+                };
+                file.set_content_disposition(self.disposition(&name));
+                file.send(req.headers(), res).await;
+            }
+            Source::Bytes(_) => {
+                let name = self.name.as_deref().unwrap_or("file");
+                let content_type = content_type.unwrap_or(mime::APPLICATION_OCTET_STREAM).to_string();
+                res.headers_mut().insert(CONTENT_DISPOSITION, self.disposition(name));
+                if let Ok(value) = HeaderValue::from_str(&content_type) {
+                    res.headers_mut().insert(CONTENT_TYPE, value);
+                }
+                if let Source::Bytes(data) = self.source {
+                    res.write_body(data).ok();
+                }
+            }
+        }
+    }
+}
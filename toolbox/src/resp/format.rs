@@ -0,0 +1,191 @@
+use salvo::{Request, http::header::ACCEPT};
+use serde::Serialize;
+
+use crate::resp::config::config;
+
+/// 响应序列化格式, JSON 以外的格式需启用对应 feature
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    #[cfg(feature = "msgpack")]
+    MsgPack,
+    #[cfg(feature = "cbor")]
+    Cbor,
+    #[cfg(feature = "xml")]
+    Xml,
+}
+
+impl Format {
+    /// 已编译的全部格式, JSON 优先
+    pub fn all() -> Vec<Self> {
+        vec![
+            Self::Json,
+            #[cfg(feature = "msgpack")]
+            Self::MsgPack,
+            #[cfg(feature = "cbor")]
+            Self::Cbor,
+            #[cfg(feature = "xml")]
+            Self::Xml,
+        ]
+    }
+
+    /// 响应的 Content-Type
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Json => "application/json; charset=utf-8",
+            #[cfg(feature = "msgpack")]
+            Self::MsgPack => "application/msgpack",
+            #[cfg(feature = "cbor")]
+            Self::Cbor => "application/cbor",
+            #[cfg(feature = "xml")]
+            Self::Xml => "application/xml; charset=utf-8",
+        }
+    }
+
+    /// 全局配置中可接受的媒体类型, 用于 406 提示
+    pub fn accepts() -> String {
+        config()
+            .formats
+            .iter()
+            .map(|f| f.media_types()[0])
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// 可匹配的媒体类型
+    fn media_types(&self) -> &'static [&'static str] {
+        match self {
+            Self::Json => &["application/json"],
+            #[cfg(feature = "msgpack")]
+            Self::MsgPack => &[
+                "application/msgpack",
+                "application/x-msgpack",
+                "application/vnd.msgpack",
+            ],
+            #[cfg(feature = "cbor")]
+            Self::Cbor => &["application/cbor"],
+            #[cfg(feature = "xml")]
+            Self::Xml => &["application/xml", "text/xml"],
+        }
+    }
+
+    /// 媒体类型不区分大小写
+    fn matches(&self, media: &str) -> bool {
+        match media.split_once('/') {
+            Some(("*", "*")) => true,
+            Some((kind, "*")) => self
+                .media_types()
+                .iter()
+                .any(|m| m.split('/').next().is_some_and(|k| k.eq_ignore_ascii_case(kind))),
+            _ => self.media_types().iter().any(|m| m.eq_ignore_ascii_case(media)),
+        }
+    }
+
+    pub fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, String> {
+        match self {
+            Self::Json => serde_json::to_vec(value).map_err(|e| e.to_string()),
+            #[cfg(feature = "msgpack")]
+            Self::MsgPack => rmp_serde::to_vec_named(value).map_err(|e| e.to_string()),
+            #[cfg(feature = "cbor")]
+            Self::Cbor => {
+                let mut buf = Vec::new();
+                ciborium::into_writer(value, &mut buf).map_err(|e| e.to_string())?;
+                Ok(buf)
+            }
+            #[cfg(feature = "xml")]
+            Self::Xml => serde_xml_rs::to_string(value)
+                .map(String::into_bytes)
+                .map_err(|e| e.to_string()),
+        }
+    }
+
+    /// 按 Accept 请求头在全局配置的格式中协商, 未携带 Accept 时为配置中的首个格式,
+    /// 无可接受的格式时返回 None
+    pub fn negotiate(req: &Request) -> Option<Self> {
+        let accept = req
+            .headers()
+            .get_all(ACCEPT)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .collect::<Vec<_>>()
+            .join(",");
+        Self::select(&config().formats, &accept)
+    }
+
+    fn select(formats: &[Self], accept: &str) -> Option<Self> {
+        if accept.trim().is_empty() {
+            return formats.first().copied();
+        }
+
+        let mut ranges: Vec<(&str, f32)> = accept
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';').map(str::trim);
+                let media = parts.next().filter(|m| !m.is_empty())?;
+                let q = parts
+                    .find_map(|p| p.strip_prefix("q="))
+                    .and_then(|q| q.parse().ok())
+                    .unwrap_or(1.0);
+                Some((media, q))
+            })
+            .collect();
+        // q=0 表示明确拒绝
+        let rejected: Vec<&str> = ranges.iter().filter(|(_, q)| *q <= 0.0).map(|(m, _)| *m).collect();
+        ranges.retain(|(_, q)| *q > 0.0);
+        // 权重相同时具体类型优先于 type/* 与 */*, 再按客户端顺序
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1).then(specificity(b.0).cmp(&specificity(a.0))));
+        ranges.iter().find_map(|(media, _)| {
+            formats
+                .iter()
+                .find(|f| {
+                    f.matches(media)
+                        && !rejected
+                            .iter()
+                            .any(|r| f.media_types().iter().any(|m| m.eq_ignore_ascii_case(r)))
+                })
+                .copied()
+        })
+    }
+}
+
+fn specificity(media: &str) -> u8 {
+    match media.split_once('/') {
+        Some(("*", "*")) => 0,
+        Some((_, "*")) => 1,
+        _ => 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn select() {
+        let formats = [Format::Json];
+        assert_eq!(Format::select(&formats, ""), Some(Format::Json));
+        assert_eq!(Format::select(&formats, "application/json"), Some(Format::Json));
+        assert_eq!(Format::select(&formats, "Application/JSON"), Some(Format::Json));
+        assert_eq!(Format::select(&formats, "APPLICATION/*;q=0.5"), Some(Format::Json));
+        assert_eq!(Format::select(&formats, "text/html, */*;q=0.1"), Some(Format::Json));
+        assert_eq!(Format::select(&formats, "text/html"), None);
+        // 明确拒绝优先于通配
+        assert_eq!(Format::select(&formats, "*/*, Application/Json;q=0"), None);
+    }
+
+    #[cfg(feature = "xml")]
+    #[test]
+    fn select_specific() {
+        let formats = [Format::Json, Format::Xml];
+        assert_eq!(Format::select(&formats, "*/*, text/xml"), Some(Format::Xml));
+        assert_eq!(
+            Format::select(&formats, "application/*, application/xml"),
+            Some(Format::Xml)
+        );
+        assert_eq!(
+            Format::select(&formats, "application/xml;q=0.5, application/*"),
+            Some(Format::Json)
+        );
+        assert_eq!(Format::select(&formats, "*/*, application/json;q=0"), Some(Format::Xml));
+    }
+}
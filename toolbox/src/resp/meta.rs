@@ -0,0 +1,155 @@
+use std::time::Duration;
+
+use salvo::{
+    Response,
+    http::{
+        HeaderName, HeaderValue,
+        cookie::Cookie,
+        header::{CACHE_CONTROL, ETAG, LOCATION, SET_COOKIE},
+    },
+};
+use serde::Serialize;
+
+use crate::resp::Res;
+
+/// 响应头, Cookie 与缓存相关的构建方法, 在写入响应时生效
+impl<T: Serialize> Res<T> {
+    /// 附加响应头, 同名时追加
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.push((name, value));
+        self
+    }
+
+    /// 附加响应头, 值不合法时忽略并记录日志
+    pub fn header_str(self, name: HeaderName, value: &str) -> Self {
+        match HeaderValue::from_str(value) {
+            Ok(value) => self.header(name, value),
+            Err(_) => {
+                tracing::warn!(%name, value, "无效的响应头");
+                self
+            }
+        }
+    }
+
+    /// 添加 Set-Cookie
+    pub fn cookie(self, cookie: Cookie<'_>) -> Self {
+        let value = cookie.encoded().to_string();
+        self.header_str(SET_COOKIE, &value)
+    }
+
+    /// 删除客户端 Cookie, path 需与设置时一致
+    pub fn remove_cookie(self, name: &str, path: &str) -> Self {
+        let mut cookie = Cookie::new(name, "");
+        cookie.set_path(path);
+        cookie.make_removal();
+        self.cookie(cookie)
+    }
+
+    /// 设置 Cache-Control, 如 `private, max-age=60`
+    pub fn cache_control(self, directives: &str) -> Self {
+        self.header_str(CACHE_CONTROL, directives)
+    }
+
+    /// 允许缓存 max_age
+    pub fn max_age(self, max_age: Duration) -> Self {
+        self.cache_control(&format!("max-age={}", max_age.as_secs()))
+    }
+
+    /// 禁止缓存
+    pub fn no_store(self) -> Self {
+        self.cache_control("no-store")
+    }
+
+    /// 设置强 ETag, 请求的 If-None-Match 匹配时返回 304
+    pub fn etag(self, tag: &str) -> Self {
+        self.header_str(ETAG, &format!("\"{tag}\""))
+    }
+
+    /// 设置弱 ETag
+    pub fn weak_etag(self, tag: &str) -> Self {
+        self.header_str(ETAG, &format!("W/\"{tag}\""))
+    }
+
+    /// 设置 Location, 通常用于 201 与 3xx
+    pub fn location(self, uri: &str) -> Self {
+        self.header_str(LOCATION, uri)
+    }
+
+    /// 写入附加的响应头
+    pub(super) fn apply_headers(&mut self, res: &mut Response) {
+        for (name, value) in std::mem::take(&mut self.headers) {
+            res.headers_mut().append(name, value);
+        }
+    }
+
+    /// If-None-Match 是否与 ETag 匹配, 按弱比较
+    pub(crate) fn not_modified(&self, if_none_match: Option<&HeaderValue>) -> bool {
+        let (Some(if_none_match), Some((_, etag))) =
+            (if_none_match, self.headers.iter().find(|(name, _)| name == ETAG))
+        else {
+            return false;
+        };
+        let (Ok(if_none_match), Ok(etag)) = (if_none_match.to_str(), etag.to_str()) else {
+            return false;
+        };
+        let strip = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+        if_none_match.trim() == "*" || if_none_match.split(',').any(|tag| strip(tag) == strip(etag))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use salvo::{
+        Router, Service, handler,
+        http::{
+            StatusCode,
+            header::{ACCEPT, IF_NONE_MATCH},
+        },
+        test::TestClient,
+    };
+
+    use super::*;
+
+    #[handler]
+    async fn show() -> Res<u32> {
+        Res::new(200, "OK".into(), 1)
+            .etag("v1")
+            .cookie(Cookie::new("session", "abc"))
+            .max_age(Duration::from_secs(60))
+    }
+
+    #[test]
+    fn not_modified() {
+        let res = Res::new(200, "OK".into(), ()).weak_etag("v1");
+        assert!(res.not_modified(Some(&HeaderValue::from_static("\"v0\", \"v1\""))));
+        assert!(res.not_modified(Some(&HeaderValue::from_static("*"))));
+        assert!(!res.not_modified(Some(&HeaderValue::from_static("\"v2\""))));
+        assert!(!res.not_modified(None));
+    }
+
+    #[tokio::test]
+    async fn headers() {
+        let service = Service::new(Router::with_path("items").get(show));
+
+        let res = TestClient::get("http://127.0.0.1/items").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert_eq!(res.headers()[ETAG], "\"v1\"");
+        assert_eq!(res.headers()[CACHE_CONTROL], "max-age=60");
+        assert_eq!(res.headers()[SET_COOKIE], "session=abc");
+
+        let res = TestClient::get("http://127.0.0.1/items")
+            .add_header(IF_NONE_MATCH, "W/\"v1\"", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_MODIFIED));
+        assert_eq!(res.headers()[ETAG], "\"v1\"");
+
+        // 406 保留处理函数设置的响应头与 Cookie
+        let res = TestClient::get("http://127.0.0.1/items")
+            .add_header(ACCEPT, "text/html", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_ACCEPTABLE));
+        assert_eq!(res.headers()[SET_COOKIE], "session=abc");
+    }
+}
@@ -0,0 +1,336 @@
+pub mod config;
+pub mod envelope;
+pub mod file;
+pub mod format;
+pub mod meta;
+pub mod page;
+pub mod problem;
+pub mod stream;
+
+use std::{borrow::Cow, sync::Arc};
+
+use salvo::{
+    Depot, Request, Response, Writer, async_trait,
+    http::{
+        HeaderName, HeaderValue, StatusCode, StatusError,
+        header::{CONTENT_TYPE, IF_NONE_MATCH, VARY},
+    },
+};
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+
+use crate::{
+    audit::{AuditEvent, AuditKind},
+    i18n::{Lang, catalog},
+    logger::{RequestId, field::USER_ID, request_id::ulid},
+    resf,
+};
+
+pub use config::{ErrorFormat, Exposure, RespConfig, StatusPolicy, install};
+pub use envelope::{Envelope, Timestamp};
+pub use file::Attachment;
+pub use format::Format;
+pub use page::Page;
+pub use problem::Problem;
+pub use stream::{JsonStream, NdJson, Sse};
+
+pub type Resp<T> = Result<Res<T>, Res<()>>;
+
+/// 响应信封
+///
+/// status 为 HTTP 状态码, code 为业务码, 未设置业务码时 JSON 中的 code 与 status 相同
+#[derive(Debug)]
+pub struct Res<T = ()> {
+    info: Arc<str>,
+    status: u16,
+    code: Option<i64>,
+    data: T,
+    request_id: Option<Arc<str>>,
+    headers: Vec<(HeaderName, HeaderValue)>,
+    localized: Option<Box<Localized>>,
+    /// 内部错误详情
+    detail: Option<Box<str>>,
+    /// 信封中由请求计算的附加字段
+    fields: Box<[(&'static str, Value)]>,
+}
+
+/// 待翻译的消息
+#[derive(Debug)]
+struct Localized {
+    key: Cow<'static, str>,
+    args: Vec<(&'static str, String)>,
+}
+
+impl<T: Serialize> Res<T> {
+    /// code 不是合法的 HTTP 状态码时作为业务码, HTTP 状态码为 400
+    pub fn new(code: u16, info: Arc<str>, data: T) -> Self {
+        Self {
+            status: code,
+            code: None,
+            info,
+            data,
+            request_id: None,
+            headers: Vec::new(),
+            localized: None,
+            detail: None,
+            fields: Box::default(),
+        }
+    }
+
+    /// 附加内部错误详情, 写入响应时记录日志, 仅在 Development 下返回给客户端
+    pub fn detail(mut self, detail: impl Into<Box<str>>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// 403 响应记录 AccessDenied 审计事件
+    fn audit(&self, req: &Request, depot: &Depot) {
+        if self.status != 403 {
+            return;
+        }
+        let mut event = AuditEvent::new(AuditKind::AccessDenied)
+            .failure()
+            .request(req, depot)
+            .resource(req.uri().path())
+            .detail("reason", self.info());
+        if let Ok(user) = depot.get::<Arc<str>>(USER_ID) {
+            event = event.actor(user.to_string());
+        }
+        event.record();
+    }
+
+    /// 记录内部错误详情, 按暴露策略决定是否拼接到 info
+    fn expose(&mut self, req: &Request, depot: &mut Depot) {
+        let request_id = RequestId::current(req, depot);
+        if let Some(full) = self.expose_with(&Lang::of(req).0, request_id.as_ref().map(|id| &*id.0)) {
+            // 访问日志记录完整详情
+            depot.insert("detail", full);
+        }
+    }
+
+    /// 不依赖请求的 expose, 返回完整详情
+    ///
+    /// Production 下 5xx 响应只返回通用提示, 原 info 与详情一同写入日志
+    fn expose_with(&mut self, lang: &str, request_id: Option<&str>) -> Option<Arc<str>> {
+        let production = config::config().exposure == Exposure::Production;
+        let server_error = StatusCode::from_u16(self.status).is_ok_and(|status| status.is_server_error());
+        let conceal = production && server_error;
+        let detail = self.detail.take();
+        if detail.is_none() && !conceal {
+            return None;
+        }
+        let request_id = request_id.unwrap_or_default();
+        let full: Arc<str> = match &detail {
+            Some(detail) => format!("{}: {detail}", self.info).into(),
+            None => self.info.clone(),
+        };
+        let detail = detail.as_deref().unwrap_or_default();
+        if self.status >= 500 {
+            tracing::error!(status = self.status, request_id, detail, "{}", self.info);
+        } else {
+            tracing::warn!(status = self.status, request_id, detail, "{}", self.info);
+        }
+        if conceal {
+            self.info = catalog().get(lang, "error.internal").unwrap_or("服务器内部错误").into();
+        } else if !production {
+            self.info = full.clone();
+        }
+        Some(full)
+    }
+
+    /// 写入响应时按请求语言将 info 翻译为目录中的 key, 目录中不存在时保留原 info
+    pub fn localize(mut self, key: impl Into<Cow<'static, str>>) -> Self {
+        let args = self.localized.take().map(|l| l.args).unwrap_or_default();
+        self.localized = Some(Box::new(Localized { key: key.into(), args }));
+        self
+    }
+
+    /// 翻译参数, 需先调用 [`localize`](Self::localize)
+    pub fn arg(mut self, name: &'static str, value: impl ToString) -> Self {
+        if let Some(localized) = &mut self.localized {
+            localized.args.push((name, value.to_string()));
+        }
+        self
+    }
+
+    fn translate(&mut self, req: &Request) {
+        if self.localized.is_some() {
+            self.translate_to(&Lang::of(req).0);
+        }
+    }
+
+    fn translate_to(&mut self, lang: &str) {
+        if let Some(localized) = self.localized.take()
+            && let Some(info) = catalog().format(lang, &localized.key, &localized.args)
+        {
+            self.info = info.into();
+        }
+    }
+
+    /// 设置业务码
+    pub fn code(mut self, code: i64) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    /// 设置 HTTP 状态码
+    pub fn status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn status_code(&self) -> u16 {
+        self.status
+    }
+
+    pub fn biz_code(&self) -> i64 {
+        self.code.unwrap_or(self.status.into())
+    }
+
+    pub fn info(&self) -> &str {
+        &self.info
+    }
+
+    /// 是否为错误响应
+    pub fn is_error(&self) -> bool {
+        StatusCode::from_u16(self.status).map_or(true, |status| status.is_client_error() || status.is_server_error())
+    }
+
+    /// 按全局策略计算 HTTP 状态码
+    fn http_status(&self) -> StatusCode {
+        match config::config().status {
+            StatusPolicy::AlwaysOk => StatusCode::OK,
+            StatusPolicy::Mirror => StatusCode::from_u16(self.status).unwrap_or(StatusCode::BAD_REQUEST),
+        }
+    }
+}
+
+impl<T: Serialize> Serialize for Res<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        config::config().envelope.serialize(self, serializer)
+    }
+}
+
+#[async_trait]
+impl<T: Serialize + Send> Writer for Res<T> {
+    async fn write(mut self, req: &mut Request, depot: &mut Depot, res: &mut Response) {
+        self.translate(req);
+        self.audit(req, depot);
+        let is_error = self.is_error();
+        // 错误响应需要请求 ID 关联日志, 未启用 Logger 请求 ID 时在此生成
+        if is_error && RequestId::current(req, depot).is_none() {
+            let id = RequestId(ulid().into());
+            req.extensions_mut().insert(id.clone());
+            depot.inject(id);
+        }
+        self.expose(req, depot);
+        if is_error && config::config().error_format == ErrorFormat::Problem {
+            self.apply_headers(res);
+            return Problem::from(self).write(req, depot, res).await;
+        }
+        let format = match Format::negotiate(req) {
+            Some(format) => format,
+            // 错误响应不被 406 覆盖
+            None if is_error || config::config().fallback_json => Format::Json,
+            None => {
+                let accepts = Format::accepts();
+                let mut not_acceptable = resf!(406, "不支持的响应格式, 可选: {accepts}")
+                    .localize("format.not_acceptable")
+                    .arg("accepts", accepts);
+                // 保留处理函数设置的响应头与 Cookie
+                not_acceptable.headers = std::mem::take(&mut self.headers);
+                return not_acceptable.render(Format::Json, req, depot, res);
+            }
+        };
+        self.render(format, req, depot, res)
+    }
+}
+
+impl<T: Serialize> Res<T> {
+    fn render(mut self, format: Format, req: &Request, depot: &mut Depot, res: &mut Response) {
+        self.translate(req);
+        let is_error = self.is_error();
+        // 错误响应携带请求 ID 便于排查
+        let envelope = &config::config().envelope;
+        if envelope.with_request_id(is_error) {
+            self.request_id = RequestId::current(req, depot).map(|id| id.0);
+        }
+        self.fields = envelope.compute(req);
+        // 成功响应的 ETag 与 If-None-Match 匹配时返回 304
+        let success = StatusCode::from_u16(self.status).is_ok_and(|status| status.is_success());
+        if success && self.not_modified(req.headers().get(IF_NONE_MATCH)) {
+            self.apply_headers(res);
+            res.status_code(StatusCode::NOT_MODIFIED);
+            return;
+        }
+        match format.encode(&self) {
+            Ok(bytes) => {
+                self.apply_headers(res);
+                res.headers_mut()
+                    .insert(CONTENT_TYPE, HeaderValue::from_static(format.content_type()));
+                if config::config().formats.len() > 1 {
+                    res.headers_mut().append(VARY, HeaderValue::from_static("Accept"));
+                }
+                res.status_code(self.http_status());
+                res.write_body(bytes).ok();
+            }
+            Err(e) => {
+                tracing::error!(error = %e, ?format, "Res write error");
+                res.render(StatusError::internal_server_error());
+            }
+        }
+
+        if is_error {
+            let error = depot.remove::<Arc<str>>("detail").unwrap_or(self.info);
+            depot.insert("error", error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use salvo::{
+        Router, Service, handler,
+        http::header::ACCEPT,
+        test::{ResponseExt, TestClient},
+    };
+    use serde_json::Value;
+
+    use super::*;
+
+    #[handler]
+    async fn found() -> Res<u32> {
+        Res::new(200, "ok".into(), 1)
+    }
+
+    #[handler]
+    async fn missing() -> Res {
+        Res::new(404, "not found".into(), ()).header(HeaderName::from_static("x-trace"), HeaderValue::from_static("1"))
+    }
+
+    #[tokio::test]
+    async fn not_acceptable() {
+        let router = Router::new()
+            .push(Router::with_path("found").get(found))
+            .push(Router::with_path("missing").get(missing));
+        let service = Service::new(router);
+
+        let res = TestClient::get("http://127.0.0.1/found")
+            .add_header(ACCEPT, "text/html", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_ACCEPTABLE));
+
+        // 错误响应回退到 JSON, 保留原状态码与响应头
+        let mut res = TestClient::get("http://127.0.0.1/missing")
+            .add_header(ACCEPT, "text/html", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_FOUND));
+        assert_eq!(res.headers()["x-trace"], "1");
+        let value: Value = res.take_json().await.unwrap();
+        assert_eq!(value["info"], "not found");
+        // 未启用 Logger 请求 ID 时错误响应也携带生成的请求 ID
+        assert!(value["request_id"].as_str().is_some_and(|id| !id.is_empty()), "{value}");
+    }
+}
@@ -0,0 +1,110 @@
+use salvo::http::{HeaderValue, header::LINK};
+use serde::Serialize;
+
+use crate::resp::Res;
+
+/// 分页数据, 放在 Res 的 data 中
+///
+/// 页码模式下 next / prev 为页码, 游标模式下为游标
+///
+/// ```ignore
+/// async fn list(pagination: Pagination) -> Resp<Page<User>> {
+///     let (users, total) = query(pagination.offset(), pagination.limit()).await?;
+///     Ok(pagination.page(users, total).into())
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: Option<u64>,
+    pub page: Option<u64>,
+    pub size: u64,
+    pub next: Option<String>,
+    pub prev: Option<String>,
+    /// 生成 Link 头使用的请求路径与查询参数
+    #[serde(skip)]
+    pub(crate) uri: Option<(String, Vec<(String, String)>)>,
+}
+
+impl<T> Page<T> {
+    /// 页码模式, page 从 1 开始
+    pub fn new(items: Vec<T>, total: u64, page: u64, size: u64) -> Self {
+        let size = size.max(1);
+        let last = total.div_ceil(size).max(1);
+        Self {
+            items,
+            total: Some(total),
+            page: Some(page),
+            size,
+            next: (page < last).then(|| (page + 1).to_string()),
+            prev: (page > 1).then(|| (page - 1).min(last).to_string()),
+            uri: None,
+        }
+    }
+
+    /// 游标模式
+    pub fn cursor(items: Vec<T>, size: u64, next: Option<String>, prev: Option<String>) -> Self {
+        Self {
+            items,
+            total: None,
+            page: None,
+            size,
+            next,
+            prev,
+            uri: None,
+        }
+    }
+
+    pub fn total(mut self, total: u64) -> Self {
+        self.total = Some(total);
+        self
+    }
+
+    /// RFC 8288 Link 头, 包含 next / prev, 页码模式下另含 first / last
+    pub fn link(&self) -> Option<HeaderValue> {
+        let (path, query) = self.uri.as_ref()?;
+        let key = if self.page.is_some() { "page" } else { "cursor" };
+        let href = |value: &str| {
+            let mut pairs: Vec<String> = query
+                .iter()
+                .filter(|(k, _)| k != "page" && k != "cursor")
+                .map(|(k, v)| format!("{}={}", encode(k), encode(v)))
+                .collect();
+            pairs.push(format!("{key}={}", encode(value)));
+            format!("{path}?{}", pairs.join("&"))
+        };
+
+        let mut links = Vec::new();
+        if let Some(next) = &self.next {
+            links.push(format!("<{}>; rel=\"next\"", href(next)));
+        }
+        if let Some(prev) = &self.prev {
+            links.push(format!("<{}>; rel=\"prev\"", href(prev)));
+        }
+        if let (Some(_), Some(total)) = (self.page, self.total) {
+            let last = total.div_ceil(self.size).max(1);
+            links.push(format!("<{}>; rel=\"first\"", href("1")));
+            links.push(format!("<{}>; rel=\"last\"", href(&last.to_string())));
+        }
+        if links.is_empty() {
+            return None;
+        }
+        HeaderValue::from_str(&links.join(", ")).ok()
+    }
+}
+
+fn encode(value: &str) -> String {
+    percent_encoding::utf8_percent_encode(value, percent_encoding::NON_ALPHANUMERIC).to_string()
+}
+
+impl<T: Serialize> From<Page<T>> for Res<Page<T>> {
+    /// 200 响应, 附带 Link 头
+    fn from(page: Page<T>) -> Self {
+        let link = page.link();
+        let res = Res::new(200, "OK".into(), page);
+        match link {
+            Some(link) => res.header(LINK, link),
+            None => res,
+        }
+    }
+}
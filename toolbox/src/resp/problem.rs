@@ -0,0 +1,228 @@
+use std::sync::Arc;
+
+use salvo::{
+    Depot, Request, Response, Writer, async_trait,
+    http::{HeaderValue, StatusCode, StatusError, header::CONTENT_TYPE},
+};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::{i18n::catalog, logger::RequestId, resp::Res};
+
+/// RFC 9457 错误详情
+///
+/// ```ignore
+/// Problem::new(403)
+///     .kind("https://example.com/probs/out-of-credit")
+///     .detail("余额不足")
+///     .extension("balance", 30)
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct Problem {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub title: String,
+    pub status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+impl Problem {
+    /// title 默认为状态码的标准描述, 状态码不合法时为 400
+    pub fn new(status: u16) -> Self {
+        let status = StatusCode::from_u16(status).unwrap_or(StatusCode::BAD_REQUEST);
+        Self {
+            kind: "about:blank".into(),
+            title: status.canonical_reason().unwrap_or_default().into(),
+            status: status.as_u16(),
+            detail: None,
+            instance: None,
+            extensions: Map::new(),
+        }
+    }
+
+    /// 问题类型 URI
+    pub fn kind(mut self, kind: impl Into<String>) -> Self {
+        self.kind = kind.into();
+        self
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    pub fn detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// 出错的具体资源, 未设置时为请求路径
+    pub fn instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+
+    /// 扩展字段, 与标准字段同名时忽略
+    pub fn extension(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.extensions.insert(key.into(), value.into());
+        self
+    }
+}
+
+impl<T: Serialize> From<Res<T>> for Problem {
+    /// info 作为 detail, 业务码与 data 作为扩展字段
+    ///
+    /// 没有请求上下文, 按目录默认语言翻译, 内部详情按暴露策略写入日志
+    fn from(mut res: Res<T>) -> Self {
+        let lang = catalog().default_lang().clone();
+        res.translate_to(&lang);
+        res.expose_with(&lang, None);
+        let mut problem = Self::new(res.status_code()).detail(res.info());
+        // 与修正后的状态码比较, 不合法的 status 作为业务码保留
+        if res.biz_code() != i64::from(problem.status) {
+            problem = problem.extension("code", res.biz_code());
+        }
+        match serde_json::to_value(&res.data) {
+            Ok(Value::Null) | Err(_) => problem,
+            Ok(data) => problem.extension("data", data),
+        }
+    }
+}
+
+#[async_trait]
+impl Writer for Problem {
+    async fn write(mut self, req: &mut Request, depot: &mut Depot, res: &mut Response) {
+        if self.instance.is_none() {
+            self.instance = Some(req.uri().path().into());
+        }
+        if let Some(id) = RequestId::current(req, depot) {
+            self.extensions
+                .entry("request_id")
+                .or_insert_with(|| id.0.as_ref().into());
+        }
+        for key in ["type", "title", "status", "detail", "instance"] {
+            self.extensions.remove(key);
+        }
+        match serde_json::to_vec(&self) {
+            Ok(bytes) => {
+                res.headers_mut()
+                    .insert(CONTENT_TYPE, HeaderValue::from_static("application/problem+json"));
+                res.status_code(StatusCode::from_u16(self.status).unwrap_or(StatusCode::BAD_REQUEST));
+                res.write_body(bytes).ok();
+            }
+            Err(e) => {
+                tracing::error!(error = ?e, "Problem write error");
+                res.render(StatusError::internal_server_error());
+            }
+        }
+        if self.status >= 400 {
+            let error = match depot.remove::<Arc<str>>("detail") {
+                Ok(detail) => detail,
+                Err(_) => self.detail.unwrap_or(self.title).into(),
+            };
+            depot.insert("error", error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use salvo::{
+        Router, Service, handler,
+        test::{ResponseExt, TestClient},
+    };
+    use std::io;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::resp::{Exposure, config};
+
+    #[test]
+    fn from_res() {
+        let problem = Problem::from(Res::new(404, "not found".into(), ()));
+        assert_eq!(problem.status, 404);
+        assert_eq!(problem.title, "Not Found");
+        assert_eq!(problem.detail.as_deref(), Some("not found"));
+        assert!(problem.extensions.is_empty());
+
+        // 不合法的状态码修正为 400, 原值作为业务码
+        let problem = Problem::from(Res::new(10001, "余额不足".into(), json!({"balance": 30})));
+        assert_eq!(problem.status, 400);
+        assert_eq!(problem.extensions["code"], 10001);
+        assert_eq!(problem.extensions["data"], json!({"balance": 30}));
+
+        let problem = Problem::from(Res::new(409, "conflict".into(), ()).code(40901));
+        assert_eq!(problem.status, 409);
+        assert_eq!(problem.extensions["code"], 40901);
+    }
+
+    #[test]
+    fn from_io_error() {
+        let problem = Problem::from(io::Error::other("disk full"));
+        assert_eq!(problem.status, 500);
+        let internal = catalog().get(catalog().default_lang(), "error.internal").unwrap();
+        let expected = match config::config().exposure {
+            Exposure::Production => internal.to_string(),
+            _ => format!("{internal}: disk full"),
+        };
+        assert_eq!(problem.detail, Some(expected));
+    }
+
+    #[test]
+    fn serialize() {
+        let problem = Problem::new(403)
+            .kind("https://example.com/probs/out-of-credit")
+            .detail("余额不足")
+            .instance("/account/1")
+            .extension("balance", 30);
+        let value = serde_json::to_value(&problem).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "type": "https://example.com/probs/out-of-credit",
+                "title": "Forbidden",
+                "status": 403,
+                "detail": "余额不足",
+                "instance": "/account/1",
+                "balance": 30,
+            })
+        );
+    }
+
+    #[handler]
+    async fn render(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+        let status = req.query::<u16>("status").unwrap_or(400);
+        Problem::new(status).extension("status", 0).write(req, depot, res).await;
+        if depot.contains_key("error") {
+            res.headers_mut().insert("x-error", HeaderValue::from_static("1"));
+        }
+    }
+
+    #[tokio::test]
+    async fn write() {
+        let service = Service::new(Router::with_path("items").get(render));
+
+        let mut res = TestClient::get("http://127.0.0.1/items?status=422")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::UNPROCESSABLE_ENTITY));
+        assert_eq!(res.headers()[CONTENT_TYPE], "application/problem+json");
+        assert!(res.headers().contains_key("x-error"));
+        let value: Value = res.take_json().await.unwrap();
+        assert_eq!(value["status"], 422);
+        assert_eq!(value["instance"], "/items");
+
+        // 非错误状态不记录 error
+        let res = TestClient::get("http://127.0.0.1/items?status=202")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::ACCEPTED));
+        assert!(!res.headers().contains_key("x-error"));
+    }
+}
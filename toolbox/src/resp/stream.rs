@@ -0,0 +1,328 @@
+use std::{io, pin::Pin, sync::Arc, time::Duration};
+
+use futures_util::{
+    Stream, StreamExt,
+    future::ready,
+    stream::{self, BoxStream},
+};
+use salvo::{
+    Depot, Request, Response, Writer, async_trait,
+    http::{
+        HeaderValue,
+        header::{CACHE_CONTROL, CONTENT_TYPE},
+    },
+    sse::{self, SseEvent, SseKeepAlive},
+};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    error::AppError,
+    i18n::Lang,
+    logger::{RequestId, request_id::ulid},
+    resp::{Res, config::config},
+};
+
+/// 占位 data, 用于拆分信封的前后两部分
+const SLOT: &str = "\u{0}stream\u{0}";
+
+/// 读取第一项, 失败时返回 Res 以便在发送响应头之前输出错误
+async fn peek<S, T, E>(stream: S) -> Result<(Option<T>, Pin<Box<S>>), Res>
+where
+    S: Stream<Item = Result<T, E>>,
+    E: Into<Res>,
+{
+    let mut stream = Box::pin(stream);
+    match stream.next().await {
+        Some(Err(err)) => Err(err.into()),
+        Some(Ok(item)) => Ok((Some(item), stream)),
+        None => Ok((None, stream)),
+    }
+}
+
+/// 发送响应头后出错时翻译与记录错误所需的请求上下文
+#[derive(Debug, Clone)]
+struct Context {
+    lang: Arc<str>,
+    request_id: Arc<str>,
+    fields: Box<[(&'static str, Value)]>,
+}
+
+impl Context {
+    fn new(req: &Request, depot: &Depot) -> Self {
+        Self {
+            lang: Lang::of(req).0,
+            // 未启用 Logger 请求 ID 时生成, 用于关联错误日志
+            request_id: RequestId::current(req, depot).map_or_else(|| ulid().into(), |id| id.0),
+            fields: config().envelope.compute(req),
+        }
+    }
+
+    /// 与 Res 写入响应时相同, 按请求语言翻译并按暴露策略处理内部错误详情
+    fn settle(&self, err: impl Into<Res>) -> Res {
+        let mut err: Res = err.into();
+        err.translate_to(&self.lang);
+        err.expose_with(&self.lang, Some(&self.request_id));
+        if config().envelope.with_request_id(true) {
+            err.request_id = Some(self.request_id.clone());
+        }
+        err.fields = self.fields.clone();
+        err
+    }
+
+    /// 发送响应头后出现的错误只能记录日志并中断响应
+    fn abort(&self, err: impl Into<Res>) -> io::Error {
+        let err = self.settle(err);
+        tracing::error!(
+            info = err.info(),
+            request_id = &*self.request_id,
+            "stream response error"
+        );
+        io::Error::other(err.info().to_string())
+    }
+
+    /// 错误项输出为一行 Res 后结束
+    fn error_line(&self, err: impl Into<Res>) -> String {
+        let err = self.settle(err);
+        tracing::error!(
+            info = err.info(),
+            request_id = &*self.request_id,
+            "stream response error"
+        );
+        to_json(&err).unwrap_or_default()
+    }
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, Res> {
+    serde_json::to_string(value).map_err(|e| AppError::internal(e).into())
+}
+
+/// 流式 JSON 数组, 逐项序列化后放在 Res 信封的 data 中, 只支持 JSON 格式
+///
+/// 第一项出错时返回错误的 Res, 之后出错时中断连接
+///
+/// ```ignore
+/// async fn export() -> JsonStream<impl Stream<Item = Result<User, Res>>> {
+///     JsonStream::new(query_users())
+/// }
+/// ```
+pub struct JsonStream<S> {
+    stream: S,
+    info: Arc<str>,
+}
+
+impl<S> JsonStream<S> {
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            info: "OK".into(),
+        }
+    }
+
+    pub fn info(mut self, info: impl Into<Arc<str>>) -> Self {
+        self.info = info.into();
+        self
+    }
+}
+
+#[async_trait]
+impl<S, T, E> Writer for JsonStream<S>
+where
+    S: Stream<Item = Result<T, E>> + Send + 'static,
+    T: Serialize + Send + 'static,
+    E: Into<Res> + Send + 'static,
+{
+    async fn write(self, req: &mut Request, depot: &mut Depot, res: &mut Response) {
+        let (first, rest) = match peek(self.stream).await {
+            Ok(peeked) => peeked,
+            Err(err) => return err.write(req, depot, res).await,
+        };
+        let first = match first.as_ref().map(to_json).transpose() {
+            Ok(first) => first,
+            Err(err) => return err.write(req, depot, res).await,
+        };
+        let mut head = Res::new(200, self.info, SLOT);
+        if config().envelope.with_request_id(false) {
+            head.request_id = RequestId::current(req, depot).map(|id| id.0);
+        }
+        head.fields = config().envelope.compute(req);
+        let envelope = to_json(&head).unwrap_or_default();
+        let slot = to_json(&SLOT).unwrap_or_default();
+        // 信封中不存在 data 字段时无法嵌入数组
+        let Some((prefix, suffix)) = envelope.split_once(&slot) else {
+            return Res::from(AppError::internal("envelope has no data field for the stream"))
+                .write(req, depot, res)
+                .await;
+        };
+        let (prefix, suffix) = (format!("{prefix}[{}", first.unwrap_or_default()), format!("]{suffix}"));
+
+        let ctx = Context::new(req, depot);
+        let items = rest.map(move |item| match item {
+            Ok(item) => to_json(&item)
+                .map(|json| format!(",{json}"))
+                .map_err(|err| ctx.abort(err)),
+            Err(err) => Err(ctx.abort(err)),
+        });
+        res.headers_mut().insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("application/json; charset=utf-8"),
+        );
+        let body = stream::once(ready(Ok(prefix)))
+            .chain(items)
+            .chain(stream::once(ready(Ok(suffix))));
+        res.stream(body);
+    }
+}
+
+/// 在出错的一项之后结束
+fn until_error<I: Send + 'static>(items: impl Stream<Item = (I, bool)> + Send + 'static) -> BoxStream<'static, I> {
+    items
+        .scan(false, |done, (item, error)| {
+            if *done {
+                return ready(None);
+            }
+            *done = error;
+            ready(Some(item))
+        })
+        .boxed()
+}
+
+/// NDJSON 响应, 每项一行 JSON
+///
+/// 第一项出错时返回错误的 Res, 之后出错时输出一行错误的 Res 并结束
+pub struct NdJson<S>(pub S);
+
+#[async_trait]
+impl<S, T, E> Writer for NdJson<S>
+where
+    S: Stream<Item = Result<T, E>> + Send + 'static,
+    T: Serialize + Send + 'static,
+    E: Into<Res> + Send + 'static,
+{
+    async fn write(self, req: &mut Request, depot: &mut Depot, res: &mut Response) {
+        let (first, rest) = match peek(self.0).await {
+            Ok(peeked) => peeked,
+            Err(err) => return err.write(req, depot, res).await,
+        };
+        let ctx = Context::new(req, depot);
+        let items = stream::iter(first.map(Ok)).chain(rest).map(move |item| {
+            match item.map_err(Into::into).and_then(|item| to_json(&item)) {
+                Ok(json) => (json + "\n", false),
+                Err(err) => (ctx.error_line(err) + "\n", true),
+            }
+        });
+        res.headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/x-ndjson"));
+        res.stream(until_error(items).map(Ok::<_, io::Error>));
+    }
+}
+
+/// Server-Sent Events 响应, 每项以 JSON 作为事件数据
+///
+/// 第一项出错时返回错误的 Res, 之后出错时发送名为 error 的事件并结束
+pub struct Sse<S> {
+    stream: S,
+    event: Option<String>,
+    keep_alive: Option<Duration>,
+}
+
+impl<S> Sse<S> {
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            event: None,
+            keep_alive: Some(Duration::from_secs(15)),
+        }
+    }
+
+    /// 事件名称
+    pub fn event(mut self, event: impl Into<String>) -> Self {
+        self.event = Some(event.into());
+        self
+    }
+
+    /// 心跳间隔, None 时不发送心跳, 默认 15 秒
+    pub fn keep_alive(mut self, interval: Option<Duration>) -> Self {
+        self.keep_alive = interval;
+        self
+    }
+}
+
+#[async_trait]
+impl<S, T, E> Writer for Sse<S>
+where
+    S: Stream<Item = Result<T, E>> + Send + 'static,
+    T: Serialize + Send + 'static,
+    E: Into<Res> + Send + 'static,
+{
+    async fn write(self, req: &mut Request, depot: &mut Depot, res: &mut Response) {
+        let (first, rest) = match peek(self.stream).await {
+            Ok(peeked) => peeked,
+            Err(err) => return err.write(req, depot, res).await,
+        };
+        let name = self.event;
+        let ctx = Context::new(req, depot);
+        let items = stream::iter(first.map(Ok)).chain(rest).map(move |item| {
+            let event = item
+                .map_err(Into::into)
+                .and_then(|item| SseEvent::default().json(item).map_err(|e| AppError::internal(e).into()));
+            match event {
+                Ok(event) => match &name {
+                    Some(name) => (event.name(name), false),
+                    None => (event, false),
+                },
+                Err(err) => (SseEvent::default().name("error").text(ctx.error_line(err)), true),
+            }
+        });
+        let events = until_error(items).map(Ok::<_, io::Error>);
+        match self.keep_alive {
+            Some(interval) => SseKeepAlive::new(events).max_interval(interval).stream(res),
+            None => sse::stream(res, events),
+        }
+        res.headers_mut()
+            .insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use salvo::{
+        Router, Service, handler,
+        http::header::ACCEPT_LANGUAGE,
+        test::{ResponseExt, TestClient},
+    };
+
+    use super::*;
+    use crate::res;
+
+    #[handler]
+    async fn export() -> NdJson<impl Stream<Item = Result<u32, Res>>> {
+        NdJson(stream::iter([
+            Ok(1),
+            Err(res!(500, "服务器内部错误").localize("error.internal").detail("db down")),
+            Ok(2),
+        ]))
+    }
+
+    #[tokio::test]
+    async fn error_after_first_item() {
+        let service = Service::new(Router::with_path("lines").get(export));
+        let body = TestClient::get("http://127.0.0.1/lines")
+            .add_header(ACCEPT_LANGUAGE, "en", true)
+            .send(&service)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        let lines: Vec<Value> = body.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], 1);
+        // 与 Res 写入时相同经过翻译与暴露策略
+        let info = lines[1]["info"].as_str().unwrap();
+        assert!(info.starts_with("Internal server error"), "{info}");
+        assert_eq!(
+            info.contains("db down"),
+            config().exposure == crate::resp::Exposure::Development
+        );
+    }
+}
@@ -11,13 +11,12 @@ use salvo::{
 use serde::Deserialize;
 use validator::Validate;
 
-use crate::{global::METADATE, reject, resp::Res};
+use crate::{global::METADATE, resf, resp::Res};
 
 /// 数据验证
 pub fn validate(data: impl Validate) -> Result<(), Res> {
     if let Err(err) = data.validate() {
         let mut msg: Vec<u8> = Vec::new();
-        write!(msg, "数据验证失败: ")?;
         for (key, value) in err.field_errors() {
             write!(msg, "{key}<")?;
             for field in value {
@@ -27,7 +26,10 @@ pub fn validate(data: impl Validate) -> Result<(), Res> {
             msg.extend(b">; ");
         }
         msg.pop();
-        return reject!(422, unsafe { String::from_utf8_unchecked(msg) });
+        let details = unsafe { String::from_utf8_unchecked(msg) };
+        return Err(resf!(422, "数据验证失败: {details}")
+            .localize("validation.failed")
+            .arg("details", details));
     }
     Ok(())
 }
@@ -1,3 +1,5 @@
 pub mod extractor;
+pub mod pagination;
 
 pub use extractor::*;
+pub use pagination::Pagination;
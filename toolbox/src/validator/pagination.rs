@@ -0,0 +1,87 @@
+use salvo::{Extractible, Request, Writer};
+use serde::Deserialize;
+use validator::{Validate, ValidationError};
+
+use crate::{
+    global::METADATE,
+    resp::{Page, Res, config::config},
+    validator::extractor::parse_query_validate,
+};
+
+fn check_size(size: u64) -> Result<(), ValidationError> {
+    if size == 0 || size > config().max_page_size {
+        return Err(ValidationError::new("page_size"));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Deserialize, Validate)]
+struct PageQuery {
+    #[validate(range(min = 1))]
+    page: Option<u64>,
+    #[validate(custom(function = "check_size"))]
+    size: Option<u64>,
+    #[validate(length(min = 1, max = 512))]
+    cursor: Option<String>,
+}
+
+/// 分页参数, 从查询参数 `page`, `size`, `cursor` 中提取并校验
+///
+/// 携带 cursor 时为游标模式, 否则为页码模式, size 不能超过全局配置的最大值
+#[derive(Debug, Clone)]
+pub struct Pagination {
+    pub page: u64,
+    pub size: u64,
+    pub cursor: Option<String>,
+    path: String,
+    query: Vec<(String, String)>,
+}
+
+impl Pagination {
+    pub fn is_cursor(&self) -> bool {
+        self.cursor.is_some()
+    }
+
+    pub fn offset(&self) -> u64 {
+        (self.page - 1).saturating_mul(self.size)
+    }
+
+    pub fn limit(&self) -> u64 {
+        self.size
+    }
+
+    /// 页码模式的分页数据
+    pub fn page<T>(&self, items: Vec<T>, total: u64) -> Page<T> {
+        let mut page = Page::new(items, total, self.page, self.size);
+        page.uri = Some((self.path.clone(), self.query.clone()));
+        page
+    }
+
+    /// 游标模式的分页数据
+    pub fn cursor_page<T>(&self, items: Vec<T>, next: Option<String>, prev: Option<String>) -> Page<T> {
+        let mut page = Page::cursor(items, self.size, next, prev);
+        page.uri = Some((self.path.clone(), self.query.clone()));
+        page
+    }
+}
+
+impl<'ex> Extractible<'ex> for Pagination {
+    fn metadata() -> &'ex salvo::extract::Metadata {
+        &METADATE
+    }
+
+    async fn extract(req: &'ex mut Request) -> Result<Self, impl Writer + Send + std::fmt::Debug + 'static> {
+        let query: PageQuery = parse_query_validate(req).await?;
+        Ok::<_, Res>(Self {
+            page: query.page.unwrap_or(1),
+            size: query.size.unwrap_or(config().default_page_size),
+            cursor: query.cursor,
+            path: req.uri().path().into(),
+            query: req
+                .queries()
+                .iter_all()
+                .flat_map(|(k, vs)| vs.iter().map(|v| (k.clone(), v.clone())))
+                .collect(),
+        })
+    }
+}